@@ -0,0 +1,568 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{gamepad::PlayerPads, storage};
+
+pub struct ActionPlugin;
+
+const BINDINGS_FILE: &str = "bindings.cfg";
+
+// スティックの遊び
+const STICK_DEADZONE: f32 = 0.25;
+
+// プレイ中に開く確認画面は、開いてからこの秒数は決定とキャンセルを受け付けない。
+// 撃ちっぱなしやボムの連打がそのまま確認画面の操作にならないようにする
+pub const PROMPT_INPUT_DELAY: f32 = 1.0;
+
+// 割り当てられるキー
+const BINDABLE_KEYS: [KeyCode; 61] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Key0,
+    KeyCode::Left,
+    KeyCode::Up,
+    KeyCode::Right,
+    KeyCode::Down,
+    KeyCode::Space,
+    KeyCode::Return,
+    KeyCode::Escape,
+    KeyCode::Back,
+    KeyCode::Tab,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::Numpad0,
+    KeyCode::Numpad2,
+    KeyCode::Numpad4,
+    KeyCode::Numpad6,
+    KeyCode::Numpad8,
+    KeyCode::F11,
+];
+
+// 割り当てられるパッドのボタン
+const BINDABLE_BUTTONS: [GamepadButtonType; 14] = [
+    GamepadButtonType::South,
+    GamepadButtonType::East,
+    GamepadButtonType::North,
+    GamepadButtonType::West,
+    GamepadButtonType::LeftTrigger,
+    GamepadButtonType::LeftTrigger2,
+    GamepadButtonType::RightTrigger,
+    GamepadButtonType::RightTrigger2,
+    GamepadButtonType::Select,
+    GamepadButtonType::Start,
+    GamepadButtonType::DPadUp,
+    GamepadButtonType::DPadDown,
+    GamepadButtonType::DPadLeft,
+    GamepadButtonType::DPadRight,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Shoot,
+    Bomb,
+    Focus,
+    Confirm,
+    Cancel,
+    Pause,
+    Fullscreen,
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Shoot,
+        Action::Bomb,
+        Action::Focus,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Pause,
+        Action::Fullscreen,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "MoveUp",
+            Action::MoveDown => "MoveDown",
+            Action::MoveLeft => "MoveLeft",
+            Action::MoveRight => "MoveRight",
+            Action::Shoot => "Shoot",
+            Action::Bomb => "Bomb",
+            Action::Focus => "Focus",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+            Action::Pause => "Pause",
+            Action::Fullscreen => "Fullscreen",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    fn in_game(&self) -> bool {
+        !matches!(self, Action::Confirm | Action::Cancel)
+    }
+
+    fn in_menu(&self) -> bool {
+        !matches!(self, Action::Shoot | Action::Bomb | Action::Focus)
+    }
+
+    // 同じ場面で使う操作同士は同じキーを共有できない。
+    // ショット・ボムと決定・キャンセルはステージクリアやコンティニューの画面で続けて押されるので同じ場面とみなす
+    fn shares_context(&self, other: Action) -> bool {
+        let on_prompt = |action: Action| {
+            matches!(
+                action,
+                Action::Shoot | Action::Bomb | Action::Confirm | Action::Cancel
+            )
+        };
+        (on_prompt(*self) && on_prompt(other))
+            || (self.in_game() && other.in_game())
+            || (self.in_menu() && other.in_menu())
+    }
+}
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+pub fn button_name(button: GamepadButtonType) -> String {
+    format!("{:?}", button)
+}
+
+pub fn is_bindable_key(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+pub fn bindable_buttons() -> impl Iterator<Item = GamepadButtonType> {
+    BINDABLE_BUTTONS.into_iter()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Binding {
+    pub keys: Vec<KeyCode>,
+    pub buttons: Vec<GamepadButtonType>,
+}
+
+impl Binding {
+    fn new(keys: &[KeyCode], buttons: &[GamepadButtonType]) -> Self {
+        Self {
+            keys: keys.to_vec(),
+            buttons: buttons.to_vec(),
+        }
+    }
+
+    // 設定ファイル用の文字列 (例: "key:Space, pad:South")
+    fn to_value(&self) -> String {
+        self.keys
+            .iter()
+            .map(|key| format!("key:{}", key_name(*key)))
+            .chain(
+                self.buttons
+                    .iter()
+                    .map(|button| format!("pad:{}", button_name(*button))),
+            )
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn from_value(value: &str) -> Self {
+        let mut binding = Binding::default();
+        for token in value.split(',').map(str::trim) {
+            if let Some(name) = token.strip_prefix("key:") {
+                if let Some(key) = BINDABLE_KEYS.into_iter().find(|key| key_name(*key) == name) {
+                    binding.keys.push(key);
+                }
+            } else if let Some(name) = token.strip_prefix("pad:") {
+                if let Some(button) = BINDABLE_BUTTONS
+                    .into_iter()
+                    .find(|button| button_name(*button) == name)
+                {
+                    binding.buttons.push(button);
+                }
+            }
+        }
+        binding
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct InputBindings(HashMap<Action, Binding>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use GamepadButtonType as Pad;
+        let bindings = [
+            (Action::MoveUp, Binding::new(&[KeyCode::Up], &[Pad::DPadUp])),
+            (
+                Action::MoveDown,
+                Binding::new(&[KeyCode::Down], &[Pad::DPadDown]),
+            ),
+            (
+                Action::MoveLeft,
+                Binding::new(&[KeyCode::Left], &[Pad::DPadLeft]),
+            ),
+            (
+                Action::MoveRight,
+                Binding::new(&[KeyCode::Right], &[Pad::DPadRight]),
+            ),
+            (Action::Shoot, Binding::new(&[KeyCode::Space], &[Pad::West])),
+            (Action::Bomb, Binding::new(&[KeyCode::X], &[Pad::North])),
+            (
+                Action::Focus,
+                Binding::new(&[KeyCode::ShiftLeft], &[Pad::RightTrigger]),
+            ),
+            (
+                Action::Confirm,
+                Binding::new(&[KeyCode::Return], &[Pad::South]),
+            ),
+            (Action::Cancel, Binding::new(&[KeyCode::Back], &[Pad::East])),
+            (
+                Action::Pause,
+                Binding::new(&[KeyCode::Escape, KeyCode::P], &[Pad::Start]),
+            ),
+            (Action::Fullscreen, Binding::new(&[KeyCode::F11], &[])),
+        ];
+        Self(bindings.into_iter().collect())
+    }
+}
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> &Binding {
+        &self.0[&action]
+    }
+
+    // 指定したキーを同じ場面で使う別の操作が使っていれば、その操作を返す
+    pub fn key_conflict(&self, action: Action, key: KeyCode) -> Option<Action> {
+        Action::ALL.into_iter().find(|other| {
+            *other != action
+                && action.shares_context(*other)
+                && self.get(*other).keys.contains(&key)
+        })
+    }
+
+    pub fn button_conflict(&self, action: Action, button: GamepadButtonType) -> Option<Action> {
+        Action::ALL.into_iter().find(|other| {
+            *other != action
+                && action.shares_context(*other)
+                && self.get(*other).buttons.contains(&button)
+        })
+    }
+
+    pub fn set_key(&mut self, action: Action, key: KeyCode) {
+        if let Some(binding) = self.0.get_mut(&action) {
+            binding.keys = vec![key];
+        }
+    }
+
+    pub fn set_button(&mut self, action: Action, button: GamepadButtonType) {
+        if let Some(binding) = self.0.get_mut(&action) {
+            binding.buttons = vec![button];
+        }
+    }
+
+    // 設定ファイルから読む。書かれていない操作は初期値のまま
+    fn load() -> Self {
+        let mut bindings = Self::default();
+        for (name, value) in storage::load_entries(BINDINGS_FILE).unwrap_or_default() {
+            if let Some(action) = Action::from_name(&name) {
+                bindings.0.insert(action, Binding::from_value(&value));
+            }
+        }
+        bindings.validate();
+        bindings
+    }
+
+    // 読み込んだ割り当てを直す。何も割り当てのない操作は初期値に戻し、
+    // ぶつかっているキーやボタンは初期値と違う方を初期値に戻す。
+    // 初期値同士はぶつからないので、戻していけば必ず終わる
+    fn validate(&mut self) {
+        let defaults = Self::default();
+        for action in Action::ALL {
+            let binding = self.0.entry(action).or_default();
+            if binding.keys.is_empty() && binding.buttons.is_empty() {
+                *binding = defaults.get(action).clone();
+            }
+        }
+        while let Some(pair) = self.first_key_conflict() {
+            for action in pair {
+                let default_keys = &defaults.get(action).keys;
+                if let Some(binding) = self.0.get_mut(&action) {
+                    if binding.keys != *default_keys {
+                        warn!("Resetting conflicting keys for {}", action.name());
+                        binding.keys = default_keys.clone();
+                    }
+                }
+            }
+        }
+        while let Some(pair) = self.first_button_conflict() {
+            for action in pair {
+                let default_buttons = &defaults.get(action).buttons;
+                if let Some(binding) = self.0.get_mut(&action) {
+                    if binding.buttons != *default_buttons {
+                        warn!("Resetting conflicting buttons for {}", action.name());
+                        binding.buttons = default_buttons.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    fn first_key_conflict(&self) -> Option<[Action; 2]> {
+        Action::ALL.into_iter().find_map(|action| {
+            self.get(action)
+                .keys
+                .iter()
+                .find_map(|key| self.key_conflict(action, *key))
+                .map(|other| [action, other])
+        })
+    }
+
+    fn first_button_conflict(&self) -> Option<[Action; 2]> {
+        Action::ALL.into_iter().find_map(|action| {
+            self.get(action)
+                .buttons
+                .iter()
+                .find_map(|button| self.button_conflict(action, *button))
+                .map(|other| [action, other])
+        })
+    }
+
+    pub fn save(&self) {
+        let entries: Vec<(String, String)> = Action::ALL
+            .into_iter()
+            .map(|action| (action.name().to_string(), self.get(action).to_value()))
+            .collect();
+        if let Err(err) = storage::save_entries(BINDINGS_FILE, &entries) {
+            warn!("Failed to save key bindings: {}", err);
+        }
+    }
+}
+
+// キーボードとパッドの入力を操作単位で読む
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    keyboard_input: Res<'w, Input<KeyCode>>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+    gamepads: Res<'w, Gamepads>,
+    player_pads: Res<'w, PlayerPads>,
+    bindings: Res<'w, InputBindings>,
+}
+
+impl Actions<'_> {
+    // 1P はキーボードと 1 つ目のパッド、2P は 2 つ目のパッドで操作する
+    fn devices(&self, player: usize) -> (bool, Option<Gamepad>) {
+        (player == 0, self.player_pads.get(player))
+    }
+
+    pub fn pressed(&self, player: usize, action: Action) -> bool {
+        let binding = self.bindings.get(action);
+        let (keyboard, gamepad) = self.devices(player);
+        (keyboard
+            && self
+                .keyboard_input
+                .any_pressed(binding.keys.iter().copied()))
+            || gamepad.is_some_and(|gamepad| {
+                binding.buttons.iter().any(|button| {
+                    self.gamepad_buttons
+                        .pressed(GamepadButton::new(gamepad, *button))
+                })
+            })
+    }
+
+    pub fn just_pressed(&self, player: usize, action: Action) -> bool {
+        let binding = self.bindings.get(action);
+        let (keyboard, gamepad) = self.devices(player);
+        (keyboard
+            && self
+                .keyboard_input
+                .any_just_pressed(binding.keys.iter().copied()))
+            || gamepad.is_some_and(|gamepad| self.pad_just_pressed(gamepad, binding))
+    }
+
+    // メニューはどの入力機器からでも操作できる
+    pub fn any_just_pressed(&self, action: Action) -> bool {
+        let binding = self.bindings.get(action);
+        self.keyboard_input
+            .any_just_pressed(binding.keys.iter().copied())
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| self.pad_just_pressed(gamepad, binding))
+    }
+
+    fn pad_just_pressed(&self, gamepad: Gamepad, binding: &Binding) -> bool {
+        binding.buttons.iter().any(|button| {
+            self.gamepad_buttons
+                .just_pressed(GamepadButton::new(gamepad, *button))
+        })
+    }
+
+    // 移動操作と左スティックを合わせた移動方向
+    pub fn direction(&self, player: usize) -> Vec2 {
+        let mut direction = Vec2::ZERO;
+        if self.pressed(player, Action::MoveLeft) {
+            direction -= Vec2::X;
+        }
+        if self.pressed(player, Action::MoveRight) {
+            direction += Vec2::X;
+        }
+        if self.pressed(player, Action::MoveUp) {
+            direction += Vec2::Y;
+        }
+        if self.pressed(player, Action::MoveDown) {
+            direction -= Vec2::Y;
+        }
+        if let Some(gamepad) = self.player_pads.get(player) {
+            let stick = Vec2::new(
+                self.gamepad_axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                    .unwrap_or(0.0),
+                self.gamepad_axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+                    .unwrap_or(0.0),
+            );
+            if stick.length() > STICK_DEADZONE {
+                direction += stick;
+            }
+        }
+        direction.clamp_length_max(1.0)
+    }
+}
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::load());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use GamepadButtonType as Pad;
+
+    #[test]
+    fn binding_value_round_trips() {
+        let binding = Binding::new(&[KeyCode::Space, KeyCode::Z], &[Pad::South, Pad::DPadUp]);
+        assert_eq!(
+            binding.to_value(),
+            "key:Space, key:Z, pad:South, pad:DPadUp"
+        );
+        assert_eq!(Binding::from_value(&binding.to_value()), binding);
+    }
+
+    #[test]
+    fn binding_value_skips_unknown_names() {
+        let binding = Binding::from_value("key:Space, key:Nope, pad:Guide, mouse:Left");
+        assert_eq!(binding, Binding::new(&[KeyCode::Space], &[]));
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        let bindings = InputBindings::default();
+        for action in Action::ALL {
+            let binding = bindings.get(action);
+            for key in &binding.keys {
+                assert_eq!(bindings.key_conflict(action, *key), None, "{:?}", action);
+            }
+            for button in &binding.buttons {
+                assert_eq!(
+                    bindings.button_conflict(action, *button),
+                    None,
+                    "{:?}",
+                    action
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn conflicts_only_within_a_shared_context() {
+        let bindings = InputBindings::default();
+        assert_eq!(
+            bindings.key_conflict(Action::Bomb, KeyCode::Space),
+            Some(Action::Shoot)
+        );
+        assert_eq!(
+            bindings.button_conflict(Action::Shoot, Pad::South),
+            Some(Action::Confirm)
+        );
+        assert_eq!(
+            bindings.button_conflict(Action::Bomb, Pad::East),
+            Some(Action::Cancel)
+        );
+        // 低速移動はプレイ中だけ、決定はメニューだけで使うので同じキーにできる
+        assert_eq!(bindings.key_conflict(Action::Focus, KeyCode::Return), None);
+    }
+
+    #[test]
+    fn validate_fills_missing_and_resets_conflicts() {
+        let mut bindings = InputBindings::default();
+        bindings.0.remove(&Action::Pause);
+        bindings.0.insert(Action::Fullscreen, Binding::default());
+        // 古い設定ファイルではショットと決定が同じボタンだった
+        bindings.set_button(Action::Shoot, Pad::South);
+        bindings.set_key(Action::Shoot, KeyCode::Z);
+        bindings.validate();
+        let defaults = InputBindings::default();
+        assert_eq!(bindings.get(Action::Pause), defaults.get(Action::Pause));
+        assert_eq!(
+            bindings.get(Action::Fullscreen),
+            defaults.get(Action::Fullscreen)
+        );
+        assert_eq!(bindings.get(Action::Shoot).buttons, [Pad::West]);
+        assert_eq!(bindings.get(Action::Shoot).keys, [KeyCode::Z]);
+        assert_eq!(bindings.get(Action::Confirm).buttons, [Pad::South]);
+    }
+}
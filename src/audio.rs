@@ -0,0 +1,303 @@
+use bevy::audio::{AddAudioSource, AudioSinkPlayback, Volume};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{
+    game::{GameState, StagePhase},
+    game_playing::{EnemyDestroyed, PlayerHit, PlayerShotFired, PlayerShotHit},
+    menu::{Menu, MenuActivated},
+    settings::Settings,
+    synth::{load_presets, load_songs, SynthSound},
+};
+
+pub struct GameAudioPlugin;
+
+// BGM の切り替えにかける秒数
+const CROSSFADE_SECONDS: f32 = 1.0;
+
+// この秒数たっても再生が始まらない効果音は捨てる
+const SFX_START_TIMEOUT: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Shot,
+    Hit,
+    Explosion,
+    PlayerHit,
+    MenuMove,
+    MenuConfirm,
+}
+
+impl Sfx {
+    const ALL: [Sfx; 6] = [
+        Sfx::Shot,
+        Sfx::Hit,
+        Sfx::Explosion,
+        Sfx::PlayerHit,
+        Sfx::MenuMove,
+        Sfx::MenuConfirm,
+    ];
+
+    // プリセットファイルでの名前
+    fn preset_name(&self) -> &'static str {
+        match self {
+            Sfx::Shot => "shot",
+            Sfx::Hit => "hit",
+            Sfx::Explosion => "explosion",
+            Sfx::PlayerHit => "player_hit",
+            Sfx::MenuMove => "menu_move",
+            Sfx::MenuConfirm => "menu_confirm",
+        }
+    }
+}
+
+// 効果音を鳴らす
+#[derive(Event, Debug)]
+pub struct PlaySfx(pub Sfx);
+
+#[derive(Resource, Debug)]
+struct SfxHandles(HashMap<Sfx, Handle<SynthSound>>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BgmTrack {
+    Title,
+    Stage,
+    Boss,
+    Ending,
+    Result,
+}
+
+impl BgmTrack {
+    const ALL: [BgmTrack; 5] = [
+        BgmTrack::Title,
+        BgmTrack::Stage,
+        BgmTrack::Boss,
+        BgmTrack::Ending,
+        BgmTrack::Result,
+    ];
+
+    fn for_state(state: GameState, stage_phase: StagePhase) -> BgmTrack {
+        match (state, stage_phase) {
+            (GameState::Start | GameState::ModeSelect | GameState::PracticeSelect, _) => {
+                BgmTrack::Title
+            }
+            (GameState::Playing, StagePhase::Boss) => BgmTrack::Boss,
+            (GameState::Playing, _) => BgmTrack::Stage,
+            (GameState::Ending, _) => BgmTrack::Ending,
+            (GameState::NameEntry | GameState::Result, _) => BgmTrack::Result,
+        }
+    }
+
+    // プリセットファイルでの曲名
+    fn preset_name(&self) -> &'static str {
+        match self {
+            BgmTrack::Title => "title",
+            BgmTrack::Stage => "stage",
+            BgmTrack::Boss => "boss",
+            BgmTrack::Ending => "ending",
+            BgmTrack::Result => "result",
+        }
+    }
+}
+
+#[derive(Resource, Debug)]
+struct BgmHandles(HashMap<BgmTrack, Handle<SynthSound>>);
+
+// 再生待ちの効果音と、待っている秒数
+#[derive(Component, Debug, Default)]
+struct PendingSfx(f32);
+
+// 再生中の BGM。gain を 0.0 ~ 1.0 で上げ下げしてクロスフェードする
+#[derive(Component, Debug)]
+struct Bgm {
+    track: BgmTrack,
+    gain: f32,
+    fading_out: bool,
+}
+
+// 効果音はファイルを使わず、起動時にプリセットから合成する
+fn synthesize_sfx(mut sounds: ResMut<Assets<SynthSound>>, mut commands: Commands) {
+    let presets = load_presets();
+    let mut handles = HashMap::new();
+    for sfx in Sfx::ALL {
+        match presets.get(sfx.preset_name()) {
+            Some(preset) => {
+                handles.insert(sfx, sounds.add(SynthSound::new(preset)));
+            }
+            None => warn!("No sound preset for {}", sfx.preset_name()),
+        }
+    }
+    commands.insert_resource(SfxHandles(handles));
+}
+
+// BGM も効果音と同じく、プリセットの楽譜から合成する
+fn synthesize_bgm(mut sounds: ResMut<Assets<SynthSound>>, mut commands: Commands) {
+    let songs = load_songs();
+    let mut handles = HashMap::new();
+    for track in BgmTrack::ALL {
+        match songs.get(track.preset_name()) {
+            Some(voices) => {
+                handles.insert(track, sounds.add(SynthSound::from_song(voices)));
+            }
+            None => warn!("No song preset for {}", track.preset_name()),
+        }
+    }
+    commands.insert_resource(BgmHandles(handles));
+}
+
+// 効果音を鳴らすプレイ中の出来事
+#[derive(SystemParam)]
+struct GameplayEvents<'w, 's> {
+    shot_fired: EventReader<'w, 's, PlayerShotFired>,
+    shot_hit: EventReader<'w, 's, PlayerShotHit>,
+    destroyed: EventReader<'w, 's, EnemyDestroyed>,
+    player_hit: EventReader<'w, 's, PlayerHit>,
+}
+
+// ゲーム中の出来事を効果音に変える
+fn sfx_from_events(
+    mut gameplay: GameplayEvents,
+    mut activated: EventReader<MenuActivated>,
+    menu_query: Query<Ref<Menu>>,
+    mut play_sfx: EventWriter<PlaySfx>,
+) {
+    // 選択が動いたメニュー (生成されたばかりのものは除く)
+    let menu_moved = menu_query
+        .iter()
+        .any(|menu| menu.is_changed() && !menu.is_added());
+    let events = [
+        (gameplay.shot_fired.iter().count() > 0, Sfx::Shot),
+        (gameplay.shot_hit.iter().count() > 0, Sfx::Hit),
+        (gameplay.destroyed.iter().count() > 0, Sfx::Explosion),
+        (gameplay.player_hit.iter().count() > 0, Sfx::PlayerHit),
+        (menu_moved, Sfx::MenuMove),
+        (activated.iter().count() > 0, Sfx::MenuConfirm),
+    ];
+    // 同じ音は 1 フレームに 1 回だけ鳴らす
+    for (happened, sfx) in events {
+        if happened {
+            play_sfx.send(PlaySfx(sfx));
+        }
+    }
+}
+
+fn play_sfx(
+    mut play_sfx: EventReader<PlaySfx>,
+    sfx_handles: Res<SfxHandles>,
+    settings: Res<Settings>,
+    mut commands: Commands,
+) {
+    for event in play_sfx.iter() {
+        let Some(handle) = sfx_handles.0.get(&event.0) else {
+            continue;
+        };
+        commands.spawn((
+            AudioSourceBundle {
+                source: handle.clone(),
+                settings: PlaybackSettings::DESPAWN
+                    .with_volume(Volume::new_relative(settings.sfx_gain())),
+            },
+            PendingSfx::default(),
+        ));
+    }
+}
+
+// 音声出力が無い環境では再生されずに残り続けるので片付ける
+fn drop_unplayed_sfx(
+    time: Res<Time>,
+    mut pending_query: Query<(Entity, &mut PendingSfx, Option<&AudioSink>)>,
+    mut commands: Commands,
+) {
+    for (entity, mut pending, sink) in pending_query.iter_mut() {
+        if sink.is_some() {
+            commands.entity(entity).remove::<PendingSfx>();
+            continue;
+        }
+        pending.0 += time.delta_seconds();
+        if pending.0 > SFX_START_TIMEOUT {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn switch_bgm(
+    state: Res<State<GameState>>,
+    stage_phase: Res<State<StagePhase>>,
+    mut bgm_query: Query<&mut Bgm>,
+    bgm_handles: Res<BgmHandles>,
+    mut commands: Commands,
+) {
+    let track = BgmTrack::for_state(*state.get(), *stage_phase.get());
+    if bgm_query
+        .iter()
+        .any(|bgm| bgm.track == track && !bgm.fading_out)
+    {
+        return;
+    }
+    for mut bgm in bgm_query.iter_mut() {
+        bgm.fading_out = true;
+    }
+    // 曲が無ければ前の曲を消すだけにする
+    let Some(handle) = bgm_handles.0.get(&track) else {
+        return;
+    };
+    commands.spawn((
+        AudioSourceBundle {
+            source: handle.clone(),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(0.0)),
+        },
+        Bgm {
+            track,
+            gain: 0.0,
+            fading_out: false,
+        },
+    ));
+}
+
+// 音声出力が無いときは AudioSink が作られないので、音量は付いているときだけ変える
+fn crossfade_bgm(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut bgm_query: Query<(Entity, &mut Bgm, Option<&AudioSink>)>,
+    mut commands: Commands,
+) {
+    let step = time.delta_seconds() / CROSSFADE_SECONDS;
+    for (entity, mut bgm, sink) in bgm_query.iter_mut() {
+        if bgm.fading_out {
+            bgm.gain = (bgm.gain - step).max(0.0);
+        } else {
+            bgm.gain = (bgm.gain + step).min(1.0);
+        }
+        if let Some(sink) = sink {
+            sink.set_volume(bgm.gain * settings.bgm_gain());
+        }
+        if bgm.fading_out && bgm.gain <= 0.0 {
+            if let Some(sink) = sink {
+                sink.stop();
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<SynthSound>()
+            .add_event::<PlaySfx>()
+            .add_systems(Startup, (synthesize_sfx, synthesize_bgm))
+            .add_systems(
+                Update,
+                (
+                    (sfx_from_events, play_sfx, drop_unplayed_sfx).chain(),
+                    (
+                        switch_bgm.run_if(
+                            state_changed::<GameState>().or_else(state_changed::<StagePhase>()),
+                        ),
+                        crossfade_bgm,
+                    )
+                        .chain(),
+                ),
+            );
+    }
+}
@@ -0,0 +1,254 @@
+use std::f32::consts::TAU;
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy::sprite::collide_aabb::collide;
+
+use crate::{
+    camera::PLAYFIELD_HEIGHT,
+    game::GameState,
+    game_playing::{
+        aim_at_player, spawn_enemy_shot, BombUsed, EnemyDestroyed, EnemyShot, GameplaySet, Player,
+        PlayerShot, PlayerShotHit, Score,
+    },
+    state_scoped::StateScoped,
+};
+
+pub struct BossPlugin;
+
+const BOSS_SIZE: f32 = 150.0;
+
+// 画面上端の外から入ってきて、この高さで左右に揺れる
+const BOSS_Y: f32 = 200.0;
+const BOSS_ENTRY_SPEED: f32 = 150.0;
+const BOSS_SWAY_WIDTH: f32 = 250.0;
+
+// ボム 1 発で減る体力
+const BOMB_DAMAGE: u32 = 15;
+
+// 時間内に倒した段階ごとのボーナス (段階の番号 + 1 を掛ける)
+const PHASE_BONUS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossPattern {
+    // 自機を狙う 3 方向弾
+    Aimed,
+    // 全方向に一斉に撃つ
+    Ring,
+    // 向きを回しながら撃つ
+    Spiral,
+}
+
+// ボスの 1 段階分。体力を削りきるか時間切れで次の段階へ進む
+#[derive(Debug)]
+pub struct BossPhase {
+    pub health: u32,
+    pub time_limit: f32,
+    pub pattern: BossPattern,
+    // 弾を撃つ間隔 (秒)
+    pub interval: f32,
+}
+
+#[derive(Debug)]
+pub struct BossData {
+    pub phases: &'static [BossPhase],
+}
+
+#[derive(Component, Debug)]
+pub struct Boss {
+    data: &'static BossData,
+    phase: usize,
+    // この段階に入る前に倒れる
+    end_phase: usize,
+    health: u32,
+    time_left: f32,
+    fire_elapsed: f32,
+    spiral_angle: f32,
+    age: f32,
+}
+
+impl Boss {
+    fn new(data: &'static BossData, phases: Range<usize>) -> Self {
+        let mut boss = Self {
+            data,
+            phase: phases.start,
+            end_phase: phases.end.min(data.phases.len()),
+            health: 0,
+            time_left: 0.0,
+            fire_elapsed: 0.0,
+            spiral_angle: 0.0,
+            age: 0.0,
+        };
+        boss.start_phase(phases.start);
+        boss
+    }
+
+    fn start_phase(&mut self, phase: usize) {
+        let data = &self.data.phases[phase];
+        self.phase = phase;
+        self.health = data.health;
+        self.time_left = data.time_limit;
+        self.fire_elapsed = 0.0;
+    }
+
+    // 今の段階の残り時間 (秒)
+    pub fn time_left(&self) -> f32 {
+        self.time_left.max(0.0)
+    }
+}
+
+// ボスを倒した。爆発は EnemyDestroyed の位置に出す
+#[derive(Event)]
+pub struct BossDefeated;
+
+// phases の範囲の段階だけ戦う
+pub fn spawn_boss(commands: &mut Commands, data: &'static BossData, phases: Range<usize>) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::PURPLE,
+                custom_size: Some(Vec2::splat(BOSS_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, PLAYFIELD_HEIGHT / 2.0 + BOSS_SIZE, 0.0),
+            ..default()
+        },
+        Boss::new(data, phases),
+        StateScoped(GameState::Playing),
+    ));
+}
+
+fn move_boss(time_step: Res<FixedTime>, mut boss_query: Query<(&mut Boss, &mut Transform)>) {
+    let delta = time_step.period.as_secs_f32();
+    for (mut boss, mut transform) in boss_query.iter_mut() {
+        if transform.translation.y > BOSS_Y {
+            transform.translation.y =
+                (transform.translation.y - BOSS_ENTRY_SPEED * delta).max(BOSS_Y);
+            continue;
+        }
+        boss.age += delta;
+        transform.translation.x = (boss.age * 0.8).sin() * BOSS_SWAY_WIDTH;
+    }
+}
+
+fn fire_boss_patterns(
+    time_step: Res<FixedTime>,
+    mut boss_query: Query<(&mut Boss, &Transform)>,
+    player_query: Query<&Transform, With<Player>>,
+    mut commands: Commands,
+) {
+    let delta = time_step.period.as_secs_f32();
+    for (mut boss, transform) in boss_query.iter_mut() {
+        // 登場し終わるまでは撃たない
+        if transform.translation.y > BOSS_Y {
+            continue;
+        }
+        boss.time_left -= delta;
+        boss.fire_elapsed += delta;
+        let phase = &boss.data.phases[boss.phase];
+        if boss.fire_elapsed < phase.interval {
+            continue;
+        }
+        boss.fire_elapsed -= phase.interval;
+        let origin = transform.translation;
+        let directions: Vec<Vec2> = match phase.pattern {
+            BossPattern::Aimed => {
+                let aim = aim_at_player(&player_query, origin);
+                [-0.2_f32, 0.0, 0.2]
+                    .into_iter()
+                    .map(|angle| Vec2::from_angle(angle).rotate(aim))
+                    .collect()
+            }
+            BossPattern::Ring => (0..16)
+                .map(|index| Vec2::from_angle(TAU * index as f32 / 16.0))
+                .collect(),
+            BossPattern::Spiral => {
+                boss.spiral_angle += 0.3;
+                (0..4)
+                    .map(|index| Vec2::from_angle(boss.spiral_angle + TAU * index as f32 / 4.0))
+                    .collect()
+            }
+        };
+        for direction in directions {
+            spawn_enemy_shot(&mut commands, origin, direction * 300.0);
+        }
+    }
+}
+
+fn check_boss_hits(
+    mut boss_query: Query<(&mut Boss, &Transform)>,
+    player_shot_query: Query<(Entity, &Transform), With<PlayerShot>>,
+    mut shot_hit: EventWriter<PlayerShotHit>,
+    mut commands: Commands,
+) {
+    for (mut boss, boss_transform) in boss_query.iter_mut() {
+        for (shot_entity, shot_transform) in player_shot_query.iter() {
+            let collision = collide(
+                shot_transform.translation,
+                Vec2::new(10.0, 10.0),
+                boss_transform.translation,
+                Vec2::splat(BOSS_SIZE),
+            );
+            if collision.is_some() {
+                commands.entity(shot_entity).despawn();
+                boss.health = boss.health.saturating_sub(1);
+                shot_hit.send(PlayerShotHit(shot_transform.translation));
+            }
+        }
+    }
+}
+
+fn bomb_boss(mut bomb_used: EventReader<BombUsed>, mut boss_query: Query<&mut Boss>) {
+    for _ in bomb_used.iter() {
+        for mut boss in boss_query.iter_mut() {
+            boss.health = boss.health.saturating_sub(BOMB_DAMAGE);
+        }
+    }
+}
+
+// 段階が終わるたびに画面の敵弾を消す
+fn advance_boss_phase(
+    mut boss_query: Query<(Entity, &mut Boss, &Transform)>,
+    enemy_shot_query: Query<Entity, With<EnemyShot>>,
+    mut score: ResMut<Score>,
+    mut defeated: EventWriter<BossDefeated>,
+    mut destroyed: EventWriter<EnemyDestroyed>,
+    mut commands: Commands,
+) {
+    for (entity, mut boss, transform) in boss_query.iter_mut() {
+        let broken = boss.health == 0;
+        if !broken && boss.time_left > 0.0 {
+            continue;
+        }
+        if broken {
+            score.0 += PHASE_BONUS * (boss.phase + 1);
+        }
+        for enemy_shot in enemy_shot_query.iter() {
+            commands.entity(enemy_shot).despawn();
+        }
+        let next_phase = boss.phase + 1;
+        if next_phase < boss.end_phase {
+            boss.start_phase(next_phase);
+        } else {
+            commands.entity(entity).despawn();
+            defeated.send(BossDefeated);
+            destroyed.send(EnemyDestroyed(transform.translation));
+        }
+    }
+}
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BossDefeated>()
+            .add_systems(
+                FixedUpdate,
+                (move_boss, fire_boss_patterns, check_boss_hits).in_set(GameplaySet),
+            )
+            .add_systems(
+                Update,
+                (bomb_boss, advance_boss_phase)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
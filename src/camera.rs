@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::{
+    action::{Action, Actions},
+    settings::Settings,
+};
+
+pub struct GameCameraPlugin;
+
+// ゲーム内の座標系の大きさ。ウィンドウの大きさに関係なくこの範囲を映す
+pub const LOGICAL_WIDTH: f32 = 1280.0;
+pub const LOGICAL_HEIGHT: f32 = 720.0;
+
+// 論理画面の右端は HUD に使い、残りの左側をプレイ領域にする
+pub const HUD_WIDTH: f32 = 400.0;
+pub const PLAYFIELD_WIDTH: f32 = LOGICAL_WIDTH - HUD_WIDTH;
+pub const PLAYFIELD_HEIGHT: f32 = LOGICAL_HEIGHT;
+
+// プレイ領域の中心をワールドの原点に置くためのカメラの位置
+pub const CAMERA_CENTER: Vec2 = Vec2::new(HUD_WIDTH / 2.0, 0.0);
+
+// プレイ領域の外を塗る色と、塗る帯の大きさ
+const LETTERBOX_COLOR: Color = Color::BLACK;
+const LETTERBOX_SIZE: f32 = 4000.0;
+
+// 全状態で使い回す唯一のカメラ
+#[derive(Component, Debug)]
+pub struct MainCamera;
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera2dBundle {
+            projection: OrthographicProjection {
+                scaling_mode: ScalingMode::AutoMin {
+                    min_width: LOGICAL_WIDTH,
+                    min_height: LOGICAL_HEIGHT,
+                },
+                ..default()
+            },
+            transform: Transform::from_translation(CAMERA_CENTER.extend(999.9)),
+            ..default()
+        },
+        MainCamera,
+    ));
+    // プレイ領域の外側を上下左右の帯で隠す。HUD はこの上に UI で描く
+    let half_size = Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT) / 2.0;
+    let bars = [
+        (Vec2::X, Vec2::splat(LETTERBOX_SIZE)),
+        (Vec2::NEG_X, Vec2::splat(LETTERBOX_SIZE)),
+        (Vec2::Y, Vec2::new(PLAYFIELD_WIDTH, LETTERBOX_SIZE)),
+        (Vec2::NEG_Y, Vec2::new(PLAYFIELD_WIDTH, LETTERBOX_SIZE)),
+    ];
+    for (side, size) in bars {
+        let center = side * (half_size + Vec2::splat(LETTERBOX_SIZE / 2.0));
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: LETTERBOX_COLOR,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(center.extend(900.0)),
+            ..default()
+        });
+    }
+}
+
+// UI も論理画面と同じ比率で拡大縮小する
+fn fit_ui_to_window(
+    mut resized_events: EventReader<WindowResized>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    if resized_events.iter().last().is_none() {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let scale = (window.width() / LOGICAL_WIDTH).min(window.height() / LOGICAL_HEIGHT);
+    if scale > 0.0 {
+        ui_scale.scale = scale as f64;
+    }
+}
+
+fn toggle_fullscreen(actions: Actions, mut settings: ResMut<Settings>) {
+    if actions.any_just_pressed(Action::Fullscreen) {
+        settings.fullscreen = !settings.fullscreen;
+        settings.save();
+    }
+}
+
+fn apply_window_mode(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    let mode = settings.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
+
+impl Plugin for GameCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_camera).add_systems(
+            Update,
+            (
+                toggle_fullscreen,
+                apply_window_mode.run_if(resource_changed::<Settings>()),
+                fit_ui_to_window,
+            )
+                .chain(),
+        );
+    }
+}
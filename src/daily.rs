@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use chrono::Local;
+
+use crate::{
+    game::{GameMode, GameState},
+    game_playing::{RestartSet, Score},
+    rng::reseed_game_rng,
+    run_stats::reset_run_stats,
+    storage,
+};
+
+pub struct DailyPlugin;
+
+const DAILY_BESTS_FILE: &str = "daily.cfg";
+
+// 今日の日付 (ローカル時刻) を YYYY-MM-DD で返す
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+// 日付の文字列からシードを作る (FNV-1a)。同じ日なら誰が遊んでも同じ展開になる
+pub fn daily_seed(date: &str) -> u64 {
+    date.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// デイリーチャレンジを遊んでいる日付。日付をまたいでも始めた日の記録にする
+#[derive(Resource, Debug, Default)]
+pub struct DailyDate(pub String);
+
+// 日付ごとの最高スコア
+#[derive(Resource, Debug, Default)]
+pub struct DailyBests(BTreeMap<String, usize>);
+
+impl DailyBests {
+    pub fn best(&self, date: &str) -> usize {
+        self.0.get(date).copied().unwrap_or(0)
+    }
+
+    // 記録を更新したら true
+    fn record(&mut self, date: &str, score: usize) -> bool {
+        if score <= self.best(date) {
+            return false;
+        }
+        self.0.insert(date.to_string(), score);
+        true
+    }
+
+    // 1 行 1 日で `日付 = スコア` の形で保存する
+    fn load() -> Self {
+        let mut daily_bests = Self::default();
+        for (date, score) in storage::load_entries(DAILY_BESTS_FILE).unwrap_or_default() {
+            if let Ok(score) = score.parse() {
+                daily_bests.record(&date, score);
+            }
+        }
+        daily_bests
+    }
+
+    fn save(&self) {
+        let entries: Vec<(String, String)> = self
+            .0
+            .iter()
+            .map(|(date, score)| (date.clone(), score.to_string()))
+            .collect();
+        if let Err(err) = storage::save_entries(DAILY_BESTS_FILE, &entries) {
+            warn!("Failed to save daily bests: {}", err);
+        }
+    }
+}
+
+// プレイを始めるたびに日付を取り直す。シードと開始時点の記録より先に決める
+fn update_daily_date(mut daily_date: ResMut<DailyDate>) {
+    let date = today();
+    if daily_date.0 != date {
+        daily_date.0 = date;
+    }
+}
+
+fn record_daily_best(
+    game_mode: Res<GameMode>,
+    score: Res<Score>,
+    daily_date: Res<DailyDate>,
+    mut daily_bests: ResMut<DailyBests>,
+) {
+    if *game_mode != GameMode::Daily {
+        return;
+    }
+    if daily_bests.record(&daily_date.0, score.0) {
+        info!("Daily best for {}: {}", daily_date.0, score.0);
+        daily_bests.save();
+    }
+}
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DailyBests::load())
+            .init_resource::<DailyDate>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                update_daily_date
+                    .before(reseed_game_rng)
+                    .before(reset_run_stats),
+            )
+            .add_systems(
+                Update,
+                update_daily_date
+                    .before(reseed_game_rng)
+                    .before(reset_run_stats)
+                    .in_set(RestartSet),
+            )
+            .add_systems(OnEnter(GameState::Result), record_daily_best);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // シードが変わると過去の日の展開も変わってしまうので、値を固定しておく
+    #[test]
+    fn daily_seed_is_stable() {
+        assert_eq!(daily_seed("2024-01-01"), 0x3763_7d7a_01d6_21a9);
+        assert_eq!(daily_seed("2024-01-01"), daily_seed("2024-01-01"));
+        assert_ne!(daily_seed("2024-01-01"), daily_seed("2024-01-02"));
+    }
+}
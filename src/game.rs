@@ -1,25 +1,264 @@
 use bevy::prelude::*;
 
+use crate::action::ActionPlugin;
+use crate::audio::GameAudioPlugin;
+use crate::boss::BossPlugin;
+use crate::camera::GameCameraPlugin;
+use crate::daily::DailyPlugin;
+use crate::game_continue::GameContinuePlugin;
+use crate::game_ending::GameEndingPlugin;
+use crate::game_feel::GameFeelPlugin;
+use crate::game_mode_select::GameModeSelectPlugin;
+use crate::game_name_entry::GameNameEntryPlugin;
+use crate::game_pause::GamePausePlugin;
 use crate::game_playing::GamePlayingPlugin;
+use crate::game_practice_select::GamePracticeSelectPlugin;
 use crate::game_result::GameResultPlugin;
 use crate::game_start::GameStartPlugin;
+use crate::gamepad::GamepadInputPlugin;
+use crate::high_score::HighScorePlugin;
+use crate::hud::HudPlugin;
+use crate::key_config::KeyConfigPlugin;
+use crate::locale::LocalePlugin;
+use crate::menu::MenuPlugin;
+use crate::options::OptionsPlugin;
+use crate::particles::ParticlePlugin;
+use crate::rng::GameRngPlugin;
+use crate::run_stats::RunStatsPlugin;
+use crate::score_attack::ScoreAttackPlugin;
+use crate::sprites::SpriteSheetPlugin;
+use crate::stage::StagePlugin;
+use crate::starfield::StarfieldPlugin;
+use crate::state_scoped::StateScopedPlugin;
+use crate::survival::SurvivalPlugin;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum GameState {
     #[default]
     Start,
+    // ゲームモードの選択
+    ModeSelect,
+    // 練習モードで始める場所の選択
+    PracticeSelect,
     Playing,
+    NameEntry,
     Result,
+    // 最後のステージをクリアした
+    Ending,
+}
+
+// 選択中のゲームモード。ハイスコアはモードごとに記録する
+#[derive(Resource, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum GameMode {
+    #[default]
+    Arcade,
+    // 時間が経つほど敵が増えて硬くなる。生き残った時間で競う
+    Survival,
+    // 決まった出現順の中、制限時間内のスコアを競う。やられる代わりに減点される
+    ScoreAttack2,
+    ScoreAttack5,
+    // 全ステージのボスと続けて戦う
+    BossRush,
+    // 好きなステージやボスの段階から始める。残機は減らず、記録も残さない
+    Practice,
+    // 日付で決まるシードで遊ぶ。記録は日ごとに残す
+    Daily,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 7] = [
+        GameMode::Arcade,
+        GameMode::Survival,
+        GameMode::ScoreAttack2,
+        GameMode::ScoreAttack5,
+        GameMode::BossRush,
+        GameMode::Practice,
+        GameMode::Daily,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Arcade => "Arcade",
+            GameMode::Survival => "Survival",
+            GameMode::ScoreAttack2 => "ScoreAttack2",
+            GameMode::ScoreAttack5 => "ScoreAttack5",
+            GameMode::BossRush => "BossRush",
+            GameMode::Practice => "Practice",
+            GameMode::Daily => "Daily",
+        }
+    }
+
+    // 制限時間 (秒)。時間制のモードでは残機が減らない
+    pub fn time_limit(&self) -> Option<f32> {
+        match self {
+            GameMode::ScoreAttack2 => Some(120.0),
+            GameMode::ScoreAttack5 => Some(300.0),
+            _ => None,
+        }
+    }
+
+    // ステージを順に進めるか
+    pub fn has_stages(&self) -> bool {
+        matches!(
+            self,
+            GameMode::Arcade | GameMode::BossRush | GameMode::Practice | GameMode::Daily
+        )
+    }
+
+    // ハイスコアを記録するか
+    pub fn is_ranked(&self) -> bool {
+        *self != GameMode::Practice
+    }
+
+    // 名前入りのランキングがあるか。デイリーチャレンジは日ごとの記録だけ残す
+    pub fn has_leaderboard(&self) -> bool {
+        self.is_ranked() && *self != GameMode::Daily
+    }
+
+    // 残機が尽きたときにコンティニューできるか
+    pub fn allows_continue(&self) -> bool {
+        matches!(self, GameMode::Arcade)
+    }
+
+    // ランキングで比べる値。Survival は生き残った時間 (1/100 秒単位)
+    pub fn ranking_value(&self, score: usize, survival_time: f32) -> usize {
+        match self {
+            GameMode::Survival => (survival_time * 100.0) as usize,
+            _ => score,
+        }
+    }
+
+    pub fn format_ranking(&self, value: usize) -> String {
+        match self {
+            GameMode::Survival => format_centiseconds(value),
+            _ => value.to_string(),
+        }
+    }
+
+    // ランキングの値を表示するときの文字列表のキー
+    pub fn ranking_key(&self) -> &'static str {
+        match self {
+            GameMode::Survival => "ranking.time",
+            _ => "ranking.score",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<GameMode> {
+        GameMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+// 練習モードで始める場所とショットの強さ
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Practice {
+    // ステージ番号 (1 始まり)
+    pub stage: usize,
+    // ボスの 1 段階だけを練習するときはその段階 (0 始まり)
+    pub boss_phase: Option<usize>,
+    pub power: usize,
+}
+
+impl Default for Practice {
+    fn default() -> Self {
+        Self {
+            stage: 1,
+            boss_phase: None,
+            power: 0,
+        }
+    }
+}
+
+// 秒数を 分:秒.1/100秒 で表す
+pub fn format_time(seconds: f32) -> String {
+    format_centiseconds((seconds.max(0.0) * 100.0) as usize)
+}
+
+fn format_centiseconds(centiseconds: usize) -> String {
+    format!(
+        "{:02}:{:02}.{:02}",
+        centiseconds / 6000,
+        centiseconds / 100 % 60,
+        centiseconds % 100
+    )
+}
+
+// GameState::Playing 中のみ意味を持つ一時停止状態
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+    // 残機が尽きてコンティニューを待っている
+    Continue,
+}
+
+// GameState::Playing 中のステージの進行。Playing 以外では Inactive
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum StagePhase {
+    #[default]
+    Inactive,
+    // ステージ名の表示
+    Intro,
+    Waves,
+    Boss,
+    // クリア時のボーナス集計
+    Clear,
+}
+
+// タイトルやポーズ画面の上に重ねて開く画面
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum OverlayState {
+    #[default]
+    None,
+    Options,
+    KeyConfig,
 }
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_state::<GameState>().add_plugins((
-            GameStartPlugin,
-            GamePlayingPlugin,
-            GameResultPlugin,
-        ));
+        app.add_state::<GameState>()
+            .add_state::<PauseState>()
+            .add_state::<StagePhase>()
+            .add_state::<OverlayState>()
+            .init_resource::<GameMode>()
+            .init_resource::<Practice>()
+            .add_plugins((
+                (
+                    StateScopedPlugin::<GameState>::default(),
+                    StateScopedPlugin::<PauseState>::default(),
+                    StateScopedPlugin::<StagePhase>::default(),
+                    StateScopedPlugin::<OverlayState>::default(),
+                ),
+                ActionPlugin,
+                GameRngPlugin,
+                GameAudioPlugin,
+                GameCameraPlugin,
+                GamepadInputPlugin,
+                HighScorePlugin,
+                DailyPlugin,
+                MenuPlugin,
+                (
+                    GameStartPlugin,
+                    GameModeSelectPlugin,
+                    GamePracticeSelectPlugin,
+                    GamePlayingPlugin,
+                    GamePausePlugin,
+                    GameContinuePlugin,
+                    GameNameEntryPlugin,
+                    GameResultPlugin,
+                    GameEndingPlugin,
+                ),
+                (StagePlugin, BossPlugin, SurvivalPlugin, ScoreAttackPlugin),
+                (OptionsPlugin, KeyConfigPlugin, LocalePlugin),
+                (RunStatsPlugin, HudPlugin),
+                (
+                    ParticlePlugin,
+                    GameFeelPlugin,
+                    SpriteSheetPlugin,
+                    StarfieldPlugin,
+                ),
+            ));
     }
 }
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{
+    action::{Action, Actions, PROMPT_INPUT_DELAY},
+    game::{GameState, PauseState},
+    game_playing::ContinueGame,
+    locale::Localized,
+    stage::playfield_overlay,
+    state_scoped::StateScoped,
+};
+
+pub struct GameContinuePlugin;
+
+// 数え始めの秒数。9 から 0 まで表示する
+const COUNTDOWN_SECONDS: f32 = 10.0;
+
+// 残りの秒数
+#[derive(Resource, Debug)]
+struct ContinueCountdown(f32);
+
+#[derive(Component, Debug)]
+struct ContinueCountdownText;
+
+fn continue_prompt(mut commands: Commands) {
+    commands.insert_resource(ContinueCountdown(COUNTDOWN_SECONDS));
+    commands
+        .spawn((playfield_overlay(), StateScoped(PauseState::Continue)))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 70.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("continue.title"),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 120.0,
+                        color: Color::YELLOW,
+                        ..default()
+                    },
+                ),
+                ContinueCountdownText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("continue.hint"),
+            ));
+        });
+}
+
+// 決定で続行、キャンセルか時間切れでゲームオーバー
+fn input_continue(
+    actions: Actions,
+    time: Res<Time>,
+    mut countdown: ResMut<ContinueCountdown>,
+    mut continue_game: EventWriter<ContinueGame>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    // 開いた直後の入力はやられる前の操作の続きなので無視する
+    let accepting = COUNTDOWN_SECONDS - countdown.0 >= PROMPT_INPUT_DELAY;
+    if accepting && actions.any_just_pressed(Action::Confirm) {
+        continue_game.send(ContinueGame);
+        next_pause_state.set(PauseState::Running);
+        return;
+    }
+    countdown.0 -= time.delta_seconds();
+    if (accepting && actions.any_just_pressed(Action::Cancel)) || countdown.0 <= 0.0 {
+        next_state.set(GameState::NameEntry);
+    }
+}
+
+fn update_countdown(
+    countdown: Res<ContinueCountdown>,
+    mut text_query: Query<&mut Text, With<ContinueCountdownText>>,
+) {
+    let value = (countdown.0.ceil() as i32 - 1).max(0).to_string();
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+impl Plugin for GameContinuePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(PauseState::Continue), continue_prompt)
+            .add_systems(
+                Update,
+                (input_continue, update_countdown)
+                    .chain()
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(in_state(PauseState::Continue)),
+            );
+    }
+}
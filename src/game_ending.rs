@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+use crate::{
+    action::{Action, Actions, PROMPT_INPUT_DELAY},
+    game::GameState,
+    game_playing::Score,
+    locale::{Locale, Localized},
+    state_scoped::StateScoped,
+};
+
+pub struct GameEndingPlugin;
+
+// エンディングを出してからの秒数
+#[derive(Resource, Debug, Default)]
+struct EndingElapsed(f32);
+
+fn ending_screen(locale: Res<Locale>, score: Res<Score>, mut commands: Commands) {
+    commands.insert_resource(EndingElapsed::default());
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GameState::Ending),
+        ))
+        .with_children(|parent| {
+            for (key, font_size) in [("ending.title", 70.0), ("ending.message", 40.0)] {
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size,
+                            color: Color::BLACK,
+                            ..default()
+                        },
+                    ),
+                    Localized(key),
+                ));
+            }
+            parent.spawn(TextBundle::from_section(
+                locale.format("ending.score", &[&score.0]),
+                TextStyle {
+                    font_size: 50.0,
+                    color: Color::BLACK,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 30.0,
+                        color: Color::GRAY,
+                        ..default()
+                    },
+                ),
+                Localized("ending.continue"),
+            ));
+        });
+}
+
+// ハイスコアの登録へ進む。ランキングに入らなければそのまま結果画面になる
+fn continue_from_ending(
+    time: Res<Time>,
+    actions: Actions,
+    mut elapsed: ResMut<EndingElapsed>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // 出た直後の入力はボス戦の操作の続きなので無視する
+    elapsed.0 += time.delta_seconds();
+    if elapsed.0 >= PROMPT_INPUT_DELAY && actions.any_just_pressed(Action::Confirm) {
+        next_state.set(GameState::NameEntry);
+    }
+}
+
+impl Plugin for GameEndingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Ending), ending_screen)
+            .add_systems(
+                Update,
+                continue_from_ending.run_if(in_state(GameState::Ending)),
+            );
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    boss::BossDefeated,
+    camera::{MainCamera, CAMERA_CENTER},
+    game_playing::{BombUsed, EnemyDestroyed, PlayerHit},
+    settings::Settings,
+};
+
+pub struct GameFeelPlugin;
+
+// 揺れが最大のときのカメラのずれ幅と傾き (ラジアン)
+const MAX_SHAKE_OFFSET: f32 = 24.0;
+const MAX_SHAKE_ANGLE: f32 = 0.04;
+
+// 1 秒あたりに減る揺れの強さ
+const TRAUMA_DECAY: f32 = 1.5;
+
+// 画面の揺れの強さ (0.0 ~ 1.0)。揺れ幅はこの 2 乗にする
+#[derive(Resource, Debug, Default)]
+pub struct ScreenShake {
+    trauma: f32,
+}
+
+impl ScreenShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
+
+// 残りの時間だけプレイの固定ステップを止める
+#[derive(Resource, Debug, Default)]
+pub struct HitStop {
+    remaining: f32,
+}
+
+impl HitStop {
+    pub fn start(&mut self, seconds: f32) {
+        self.remaining = self.remaining.max(seconds);
+    }
+}
+
+// 固定ステップのプレイ処理に付ける実行条件。止めている間は進めない
+pub fn not_hit_stopped(hit_stop: Res<HitStop>) -> bool {
+    hit_stop.remaining <= 0.0
+}
+
+fn feel_from_events(
+    settings: Res<Settings>,
+    mut player_hit: EventReader<PlayerHit>,
+    mut bomb_used: EventReader<BombUsed>,
+    mut destroyed: EventReader<EnemyDestroyed>,
+    mut boss_defeated: EventReader<BossDefeated>,
+    mut screen_shake: ResMut<ScreenShake>,
+    mut hit_stop: ResMut<HitStop>,
+) {
+    // ヒットストップも揺れと同じ設定で切れるようにする
+    let hit_stop_seconds = |seconds: f32| if settings.screen_shake { seconds } else { 0.0 };
+    if player_hit.iter().count() > 0 {
+        screen_shake.add_trauma(0.8);
+    }
+    if bomb_used.iter().count() > 0 {
+        screen_shake.add_trauma(0.6);
+        hit_stop.start(hit_stop_seconds(0.1));
+    }
+    // ボスを倒した瞬間は長めに止めて見せる
+    if boss_defeated.iter().count() > 0 {
+        screen_shake.add_trauma(1.0);
+        hit_stop.start(hit_stop_seconds(0.3));
+    }
+    let destroyed = destroyed.iter().count();
+    if destroyed > 0 {
+        screen_shake.add_trauma(0.05 * destroyed as f32);
+    }
+}
+
+fn shake_camera(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut screen_shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    screen_shake.trauma = (screen_shake.trauma - TRAUMA_DECAY * time.delta_seconds()).max(0.0);
+    let shake = if settings.screen_shake {
+        screen_shake.trauma * screen_shake.trauma
+    } else {
+        0.0
+    };
+    let mut rng = rand::thread_rng();
+    for mut transform in camera_query.iter_mut() {
+        let offset = Vec2::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0));
+        let offset = offset * MAX_SHAKE_OFFSET * shake;
+        transform.translation.x = CAMERA_CENTER.x + offset.x;
+        transform.translation.y = CAMERA_CENTER.y + offset.y;
+        transform.rotation =
+            Quat::from_rotation_z(rng.gen_range(-1.0_f32, 1.0) * MAX_SHAKE_ANGLE * shake);
+    }
+}
+
+// Time 全体は止めないので、Update で動くカウントダウンなどは止まらない
+fn update_hit_stop(time: Res<Time>, mut hit_stop: ResMut<HitStop>) {
+    hit_stop.remaining = (hit_stop.remaining - time.delta_seconds()).max(0.0);
+}
+
+impl Plugin for GameFeelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenShake>()
+            .init_resource::<HitStop>()
+            .add_systems(
+                Update,
+                (feel_from_events, (shake_camera, update_hit_stop)).chain(),
+            );
+    }
+}
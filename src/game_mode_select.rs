@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::{GameMode, GameState},
+    locale::Localized,
+    menu::{spawn_localized_menu, MenuActivated, MenuCancelled, MenuLayout},
+    state_scoped::StateScoped,
+};
+
+pub struct GameModeSelectPlugin;
+
+#[derive(Component, Debug, Clone, Copy)]
+enum ModeSelectMenu {
+    Mode(GameMode),
+    Back,
+}
+
+fn mode_label(mode: GameMode) -> &'static str {
+    match mode {
+        GameMode::Arcade => "mode.arcade",
+        GameMode::Survival => "mode.survival",
+        GameMode::ScoreAttack2 => "mode.score_attack_2",
+        GameMode::ScoreAttack5 => "mode.score_attack_5",
+        GameMode::BossRush => "mode.boss_rush",
+        GameMode::Practice => "mode.practice",
+        GameMode::Daily => "mode.daily",
+    }
+}
+
+fn mode_select_menu(mut commands: Commands) {
+    let mut items: Vec<(ModeSelectMenu, &'static str)> = GameMode::ALL
+        .into_iter()
+        .map(|mode| (ModeSelectMenu::Mode(mode), mode_label(mode)))
+        .collect();
+    items.push((ModeSelectMenu::Back, "mode_select.back"));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GameState::ModeSelect),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 50.0,
+                        color: Color::BLACK,
+                        ..default()
+                    },
+                ),
+                Localized("mode_select.title"),
+            ));
+            spawn_localized_menu(parent, MenuLayout::List, &items);
+        });
+}
+
+fn input_mode_select_menu(
+    mut activated: EventReader<MenuActivated>,
+    mut cancelled: EventReader<MenuCancelled>,
+    mode_select_menu_query: Query<&ModeSelectMenu>,
+    mut game_mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for event in activated.iter() {
+        let Ok(mode_select_menu) = mode_select_menu_query.get(event.0) else {
+            continue;
+        };
+        match mode_select_menu {
+            // 練習は始める場所を選んでから
+            ModeSelectMenu::Mode(GameMode::Practice) => {
+                *game_mode = GameMode::Practice;
+                next_state.set(GameState::PracticeSelect);
+            }
+            ModeSelectMenu::Mode(mode) => {
+                *game_mode = *mode;
+                next_state.set(GameState::Playing);
+            }
+            ModeSelectMenu::Back => {
+                next_state.set(GameState::Start);
+            }
+        }
+    }
+    if cancelled
+        .iter()
+        .any(|event| mode_select_menu_query.contains(event.0))
+    {
+        next_state.set(GameState::Start);
+    }
+}
+
+impl Plugin for GameModeSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::ModeSelect), mode_select_menu)
+            .add_systems(
+                Update,
+                input_mode_select_menu.run_if(in_state(GameState::ModeSelect)),
+            );
+    }
+}
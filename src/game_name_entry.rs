@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::{
+    action::{Action, Actions},
+    game::{GameMode, GameState},
+    game_playing::Score,
+    high_score::HighScores,
+    locale::{Locale, Localized},
+    run_stats::RunStats,
+    state_scoped::StateScoped,
+};
+
+pub struct GameNameEntryPlugin;
+
+const NAME_LENGTH: usize = 3;
+
+const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// 入力中のイニシャル (LETTERS の添字) とカーソル位置
+#[derive(Resource, Debug, Default)]
+struct NameEntry {
+    letters: [usize; NAME_LENGTH],
+    cursor: usize,
+}
+
+impl NameEntry {
+    fn name(&self) -> String {
+        self.letters
+            .iter()
+            .map(|letter| LETTERS[*letter] as char)
+            .collect()
+    }
+}
+
+// 何文字目を表示するか
+#[derive(Component, Debug)]
+struct NameEntryLetter(usize);
+
+fn name_entry(
+    locale: Res<Locale>,
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    game_mode: Res<GameMode>,
+    high_scores: Res<HighScores>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) {
+    // ランキングに入らなければ (記録しないモードも) そのまま結果画面へ
+    let value = run_stats.ranking_value(*game_mode, score.0);
+    if !game_mode.has_leaderboard() || !high_scores.qualifies(*game_mode, value) {
+        next_state.set(GameState::Result);
+        return;
+    }
+    commands.insert_resource(NameEntry::default());
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GameState::NameEntry),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 50.0,
+                        color: Color::BLACK,
+                        ..default()
+                    },
+                ),
+                Localized("name_entry.title"),
+            ));
+            parent.spawn(TextBundle::from_section(
+                locale.format(game_mode.ranking_key(), &[&game_mode.format_ranking(value)]),
+                TextStyle {
+                    font_size: 50.0,
+                    color: Color::BLACK,
+                    ..default()
+                },
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for index in 0..NAME_LENGTH {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "A",
+                                TextStyle {
+                                    font_size: 100.0,
+                                    color: Color::BLACK,
+                                    ..default()
+                                },
+                            )
+                            .with_style(Style {
+                                margin: UiRect::horizontal(Val::Px(10.0)),
+                                ..default()
+                            }),
+                            NameEntryLetter(index),
+                        ));
+                    }
+                });
+        });
+}
+
+fn input_name_entry(
+    actions: Actions,
+    name_entry: Option<ResMut<NameEntry>>,
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    game_mode: Res<GameMode>,
+    mut high_scores: ResMut<HighScores>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut name_entry) = name_entry else {
+        return;
+    };
+    let cursor = name_entry.cursor;
+    // 上下で文字を選び、左右で入力位置を移す
+    if actions.any_just_pressed(Action::MoveUp) {
+        name_entry.letters[cursor] = (name_entry.letters[cursor] + 1) % LETTERS.len();
+    }
+    if actions.any_just_pressed(Action::MoveDown) {
+        name_entry.letters[cursor] =
+            (name_entry.letters[cursor] + LETTERS.len() - 1) % LETTERS.len();
+    }
+    if actions.any_just_pressed(Action::MoveLeft) || actions.any_just_pressed(Action::Cancel) {
+        name_entry.cursor = cursor.saturating_sub(1);
+    }
+    if actions.any_just_pressed(Action::MoveRight) {
+        name_entry.cursor = (cursor + 1).min(NAME_LENGTH - 1);
+    }
+    if actions.any_just_pressed(Action::Confirm) {
+        if cursor + 1 < NAME_LENGTH {
+            name_entry.cursor += 1;
+        } else {
+            let value = run_stats.ranking_value(*game_mode, score.0);
+            let rank = high_scores.insert(*game_mode, name_entry.name(), value);
+            high_scores.save();
+            info!("High score registered at rank {}", rank + 1);
+            next_state.set(GameState::Result);
+        }
+    }
+}
+
+fn update_name_entry(
+    name_entry: Option<Res<NameEntry>>,
+    mut letter_query: Query<(&mut Text, &NameEntryLetter)>,
+) {
+    let Some(name_entry) = name_entry else {
+        return;
+    };
+    if !name_entry.is_changed() {
+        return;
+    }
+    for (mut text, letter) in letter_query.iter_mut() {
+        text.sections[0].value = (LETTERS[name_entry.letters[letter.0]] as char).to_string();
+        text.sections[0].style.color = if letter.0 == name_entry.cursor {
+            Color::RED
+        } else {
+            Color::BLACK
+        };
+    }
+}
+
+fn delete_name_entry(mut commands: Commands) {
+    commands.remove_resource::<NameEntry>();
+}
+
+impl Plugin for GameNameEntryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::NameEntry), name_entry)
+            .add_systems(
+                Update,
+                (input_name_entry, update_name_entry)
+                    .chain()
+                    .run_if(in_state(GameState::NameEntry)),
+            )
+            .add_systems(OnExit(GameState::NameEntry), delete_name_entry);
+    }
+}
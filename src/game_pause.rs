@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+use crate::{
+    action::{Action, Actions},
+    game::{GameState, OverlayState, PauseState},
+    game_playing::{delete_playing, RestartPlaying},
+    locale::Localized,
+    menu::{spawn_localized_menu, MenuActivated, MenuCancelled, MenuLayout},
+    state_scoped::StateScoped,
+};
+
+pub struct GamePausePlugin;
+
+#[derive(Component, Debug, Clone, Copy)]
+enum PauseMenu {
+    Resume,
+    Restart,
+    Options,
+    QuitToTitle,
+}
+
+fn toggle_pause(
+    actions: Actions,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if !actions.any_just_pressed(Action::Pause) {
+        return;
+    }
+    match pause_state.get() {
+        PauseState::Running => next_pause_state.set(PauseState::Paused),
+        PauseState::Paused => next_pause_state.set(PauseState::Running),
+        PauseState::Continue => {}
+    }
+}
+
+fn pause_on_focus_lost(
+    mut focus_events: EventReader<WindowFocused>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if focus_events.iter().any(|event| !event.focused) {
+        next_pause_state.set(PauseState::Paused);
+    }
+}
+
+fn pause_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                ..default()
+            },
+            StateScoped(PauseState::Paused),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 50.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ),
+                        Localized("pause.title"),
+                    ));
+                    spawn_localized_menu(
+                        parent,
+                        MenuLayout::Vertical,
+                        &[
+                            (PauseMenu::Resume, "menu.resume"),
+                            (PauseMenu::Restart, "menu.restart"),
+                            (PauseMenu::Options, "menu.options"),
+                            (PauseMenu::QuitToTitle, "menu.quit_to_title"),
+                        ],
+                    );
+                });
+        });
+}
+
+fn input_pause_menu(
+    mut activated: EventReader<MenuActivated>,
+    mut cancelled: EventReader<MenuCancelled>,
+    pause_menu_query: Query<&PauseMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut next_overlay_state: ResMut<NextState<OverlayState>>,
+    mut restart_events: EventWriter<RestartPlaying>,
+) {
+    for event in activated.iter() {
+        let Ok(pause_menu) = pause_menu_query.get(event.0) else {
+            continue;
+        };
+        match pause_menu {
+            PauseMenu::Resume => {
+                next_pause_state.set(PauseState::Running);
+            }
+            PauseMenu::Restart => {
+                restart_events.send(RestartPlaying);
+                next_pause_state.set(PauseState::Running);
+            }
+            PauseMenu::Options => {
+                next_overlay_state.set(OverlayState::Options);
+            }
+            PauseMenu::QuitToTitle => {
+                next_state.set(GameState::Start);
+            }
+        }
+    }
+    // 上に開いた画面でのキャンセルは無視する
+    if cancelled
+        .iter()
+        .any(|event| pause_menu_query.contains(event.0))
+    {
+        next_pause_state.set(PauseState::Running);
+    }
+}
+
+fn reset_pause(mut next_pause_state: ResMut<NextState<PauseState>>) {
+    next_pause_state.set(PauseState::Running);
+}
+
+impl Plugin for GamePausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(PauseState::Paused), pause_menu)
+            .add_systems(
+                Update,
+                (
+                    // オプション画面を開いている間はポーズを解除しない
+                    toggle_pause.run_if(in_state(OverlayState::None)),
+                    pause_on_focus_lost.run_if(in_state(PauseState::Running)),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                // やり直しの合図は消す側と作り直す側が同じフレームで受け取る
+                input_pause_menu
+                    .before(delete_playing)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(in_state(PauseState::Paused)),
+            )
+            .add_systems(OnExit(GameState::Playing), reset_pause);
+    }
+}
@@ -2,93 +2,195 @@ use bevy::prelude::*;
 use bevy::sprite::collide_aabb::collide;
 use rand::Rng;
 
-use crate::game::GameState;
+use crate::{
+    action::{Action, Actions},
+    camera::{PLAYFIELD_HEIGHT, PLAYFIELD_WIDTH},
+    game::{GameMode, GameState, PauseState, Practice},
+    game_feel::not_hit_stopped,
+    gamepad::PlayerPads,
+    particles::{ParticleEffect, ParticleEmitter},
+    rng::GameRng,
+    run_stats::RunStats,
+    sprites::SpriteKind,
+    state_scoped::StateScoped,
+};
 
 pub struct GamePlayingPlugin;
 
+// プレイヤー番号 (0: 1P, 1: 2P)
 #[derive(Component)]
-pub struct Player;
+pub struct Player(pub usize);
 
 #[derive(Component)]
 pub struct Enemy;
 
+// 敵の残り耐久。ショットが当たるたびに 1 減り、0 で倒れる
+#[derive(Component)]
+struct EnemyHealth(u32);
+
 #[derive(Component)]
 pub struct PlayerShot;
 
 #[derive(Component)]
 pub struct EnemyShot;
 
+// 敵弾の速度 (ピクセル/秒)
+#[derive(Component)]
+struct ShotVelocity(Vec2);
+
+// 画面外に出てからこの距離だけ離れた弾や敵は消す
+const OFFSCREEN_MARGIN: f32 = 50.0;
+
 #[derive(Component)]
 struct Collider;
 
 #[derive(Resource)]
-struct Score(usize);
+pub struct Score(pub usize);
 
-#[derive(Component, Debug)]
-struct ScoreUI;
+// 残りボム数
+#[derive(Resource)]
+pub struct Bombs(pub usize);
 
-fn setup(mut commands: Commands) {
-    // Camera
-    commands.spawn(Camera2dBundle::default());
-    // Player
+// Update で受け付けたボムの入力。固定ステップで 1 回だけ消費する
+#[derive(Resource)]
+struct BombRequested(bool);
+
+// Update で受け付けたショットの入力。撃ったプレイヤーの番号を固定ステップで 1 回だけ消費する
+#[derive(Resource)]
+struct ShotRequested(Vec<usize>);
+
+// 残機数。0 になったらゲームオーバー
+#[derive(Resource)]
+pub struct Lives(pub usize);
+
+// ショットの強さ。1 上がるごとに弾が 1 発増える
+#[derive(Resource)]
+pub struct Power(pub usize);
+
+pub const MAX_POWER: usize = 4;
+
+// このプレイでコンティニューした回数
+#[derive(Resource)]
+pub struct Continues(pub usize);
+
+// 現在のステージ番号 (1 始まり)
+#[derive(Resource)]
+pub struct Stage(pub usize);
+
+// やられた後の無敵時間。中身は残りの秒数
+#[derive(Component)]
+struct Invulnerable(f32);
+
+const INVULNERABLE_SECONDS: f32 = 2.0;
+
+// 時間制のモードでやられたときに引かれる点数
+const HIT_PENALTY: usize = 10;
+
+// 敵弾がかすった (1 発につき 1 回だけ数える)
+#[derive(Component)]
+struct Grazed;
+
+// プレイ中のゲームをやり直す
+#[derive(Event)]
+pub struct RestartPlaying;
+
+// やり直しで作り直すシステムの集まり。古いエンティティを消した後に動く
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RestartSet;
+
+// 固定ステップで進めるプレイ処理の集まり。ポーズ中とヒットストップ中は止める
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameplaySet;
+
+// 自機のショットが 1 発撃たれた
+#[derive(Event)]
+pub struct PlayerShotFired;
+
+// 自機のショットが敵に当たった。中身は当たった位置
+#[derive(Event)]
+pub struct PlayerShotHit(pub Vec3);
+
+// 敵が倒された。中身は倒された位置
+#[derive(Event)]
+pub struct EnemyDestroyed(pub Vec3);
+
+// 敵弾が自機をかすった
+#[derive(Event)]
+pub struct EnemyShotGrazed;
+
+// ボムが使われた
+#[derive(Event)]
+pub struct BombUsed;
+
+// 自機がやられた。中身はやられた位置
+#[derive(Event)]
+pub struct PlayerHit(pub Vec3);
+
+// コンティニューして同じプレイを続ける
+#[derive(Event)]
+pub struct ContinueGame;
+
+fn spawn_player(commands: &mut Commands, index: usize) {
+    let (x, color) = match index {
+        0 => (0.0, Color::WHITE),
+        _ => (150.0, Color::CYAN),
+    };
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
+                color,
                 rect: Some(Rect {
                     min: Vec2::new(0.0, 0.0),
                     max: Vec2::new(100.0, 100.0),
                 }),
                 ..default()
             },
+            transform: Transform {
+                translation: Vec3::new(x, 0.0, 0.0),
+                ..default()
+            },
             ..default()
         },
-        Player,
+        Player(index),
+        SpriteKind::Player,
         Collider,
+        // 機体の後ろから噴射を出す
+        ParticleEmitter::new(ParticleEffect::EngineTrail, Vec2::new(0.0, -50.0), 0.02),
+        StateScoped(GameState::Playing),
     ));
+}
+
+fn setup(
+    player_pads: Res<PlayerPads>,
+    game_mode: Res<GameMode>,
+    practice: Res<Practice>,
+    mut commands: Commands,
+) {
+    // Player
+    spawn_player(&mut commands, 0);
+    if player_pads.get(1).is_some() {
+        spawn_player(&mut commands, 1);
+    }
     // Score
     commands.insert_resource(Score(0));
-    // Score UI
-    commands
-        .spawn((
-            NodeBundle {
-                style: Style { ..default() },
-                ..default()
-            },
-            ScoreUI,
-        ))
-        .with_children(|parent| {
-            parent.spawn(TextBundle {
-                text: Text {
-                    sections: vec![
-                        TextSection {
-                            value: "Score: ".to_string(),
-                            style: TextStyle {
-                                font_size: 50.0,
-                                color: Color::BLACK,
-                                ..default()
-                            },
-                        },
-                        TextSection {
-                            value: "0".to_string(),
-                            style: TextStyle {
-                                font_size: 50.0,
-                                color: Color::BLACK,
-                                ..default()
-                            },
-                        },
-                    ],
-                    ..default()
-                },
-                ..default()
-            });
-        });
+    commands.insert_resource(Bombs(3));
+    commands.insert_resource(BombRequested(false));
+    commands.insert_resource(ShotRequested(Vec::new()));
+    commands.insert_resource(Lives(3));
+    // 練習では選んだステージと強さから始める
+    let (stage, power) = match *game_mode {
+        GameMode::Practice => (practice.stage, practice.power),
+        _ => (1, 0),
+    };
+    commands.insert_resource(Power(power));
+    commands.insert_resource(Stage(stage));
+    commands.insert_resource(Continues(0));
 }
 
-fn create_enemy(mut commands: Commands) {
-    let hoge: i32 = rand::thread_rng().gen_range(0, 100);
-    // 一定周期で敵を生成
-    if hoge == 0 {
-        commands.spawn((
+// プレイ領域の上端の少し外に敵を出す
+pub fn spawn_enemy(commands: &mut Commands, x: f32, health: u32) -> Entity {
+    commands
+        .spawn((
             SpriteBundle {
                 sprite: Sprite {
                     rect: Some(Rect {
@@ -98,166 +200,278 @@ fn create_enemy(mut commands: Commands) {
                     ..default()
                 },
                 transform: Transform {
-                    translation: Vec3::new(rand::thread_rng().gen_range(-300.0, 300.0), 300.0, 0.0),
+                    translation: Vec3::new(x, PLAYFIELD_HEIGHT / 2.0 + 25.0, 0.0),
                     ..default()
                 },
                 ..default()
             },
             Enemy,
+            EnemyHealth(health),
+            SpriteKind::Enemy,
             Collider,
-        ));
+            StateScoped(GameState::Playing),
+        ))
+        .id()
+}
+
+pub fn spawn_enemy_shot(commands: &mut Commands, translation: Vec3, velocity: Vec2) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                rect: Some(Rect {
+                    min: Vec2::new(0.0, 0.0),
+                    max: Vec2::new(10.0, 10.0),
+                }),
+                ..default()
+            },
+            transform: Transform {
+                translation,
+                ..default()
+            },
+            ..default()
+        },
+        EnemyShot,
+        ShotVelocity(velocity),
+        SpriteKind::EnemyShot,
+        Collider,
+        StateScoped(GameState::Playing),
+    ));
+}
+
+// origin から一番近い自機への向き。自機がいなければ真下
+pub fn aim_at_player(player_query: &Query<&Transform, With<Player>>, origin: Vec3) -> Vec2 {
+    let target = player_query
+        .iter()
+        .map(|player_transform| player_transform.translation)
+        .min_by(|a, b| a.distance(origin).total_cmp(&b.distance(origin)))
+        .unwrap_or(origin - Vec3::Y);
+    (target - origin).truncate().normalize_or_zero()
+}
+
+fn is_offscreen(translation: Vec3) -> bool {
+    let half_size = Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT) / 2.0 + OFFSCREEN_MARGIN;
+    translation.x.abs() > half_size.x || translation.y.abs() > half_size.y
+}
+
+// 2つ目のパッドが途中で繋がったら 2P を参加させる
+fn join_second_player(
+    player_pads: Res<PlayerPads>,
+    player_query: Query<&Player>,
+    mut commands: Commands,
+) {
+    if player_pads.get(1).is_none() || player_query.iter().any(|player| player.0 == 1) {
+        return;
+    }
+    spawn_player(&mut commands, 1);
+}
+
+// ボムと同じく、押した瞬間は Update で拾っておく
+fn request_shot(
+    actions: Actions,
+    player_query: Query<&Player>,
+    mut shot_requested: ResMut<ShotRequested>,
+) {
+    for player in player_query.iter() {
+        if actions.just_pressed(player.0, Action::Shoot) && !shot_requested.0.contains(&player.0) {
+            shot_requested.0.push(player.0);
+        }
     }
 }
 
 fn create_player_shot(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Transform, With<Player>>,
+    power: Res<Power>,
+    query: Query<(&Transform, &Player)>,
+    mut shot_requested: ResMut<ShotRequested>,
+    mut shot_fired: EventWriter<PlayerShotFired>,
     mut commands: Commands,
 ) {
-    let player_transform = query.single_mut();
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        // shot
-        commands.spawn((
-            SpriteBundle {
-                sprite: Sprite {
-                    rect: Some(Rect {
-                        min: Vec2::new(0.0, 0.0),
-                        max: Vec2::new(10.0, 10.0),
-                    }),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: player_transform.translation,
+    let requested = std::mem::take(&mut shot_requested.0);
+    for (player_transform, player) in query.iter() {
+        if !requested.contains(&player.0) {
+            continue;
+        }
+        // パワーの分だけ横に並べて撃つ
+        for index in 0..=power.0 {
+            let offset = (index as f32 - power.0 as f32 / 2.0) * 20.0;
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        rect: Some(Rect {
+                            min: Vec2::new(0.0, 0.0),
+                            max: Vec2::new(10.0, 10.0),
+                        }),
+                        ..default()
+                    },
+                    transform: Transform {
+                        translation: player_transform.translation + Vec3::X * offset,
+                        ..default()
+                    },
                     ..default()
                 },
-                ..default()
-            },
-            PlayerShot,
-            Collider,
-        ));
+                PlayerShot,
+                SpriteKind::PlayerShot,
+                Collider,
+                StateScoped(GameState::Playing),
+            ));
+            // 命中率は弾 1 発ごとに数える
+            shot_fired.send(PlayerShotFired);
+        }
+    }
+}
+
+// 押した瞬間は固定ステップの回数に関係なく 1 回だけ拾う
+fn request_bomb(
+    actions: Actions,
+    player_query: Query<&Player>,
+    mut bomb_requested: ResMut<BombRequested>,
+) {
+    if player_query
+        .iter()
+        .any(|player| actions.just_pressed(player.0, Action::Bomb))
+    {
+        bomb_requested.0 = true;
     }
 }
 
-fn create_enemy_shot(mut query: Query<&mut Transform, With<Enemy>>, mut commands: Commands) {
+// ボムで消えるもの
+type BombTarget = Or<(With<Enemy>, With<EnemyShot>)>;
+
+// ボム: 画面内の敵と敵弾をまとめて消す
+fn use_bomb(
+    mut bomb_requested: ResMut<BombRequested>,
+    target_query: Query<(Entity, &Transform, Option<&Enemy>), BombTarget>,
+    mut bombs: ResMut<Bombs>,
+    mut score: ResMut<Score>,
+    mut events: (EventWriter<BombUsed>, EventWriter<EnemyDestroyed>),
+    mut commands: Commands,
+) {
+    let requested = std::mem::take(&mut bomb_requested.0);
+    if !requested || bombs.0 == 0 {
+        return;
+    }
+    bombs.0 -= 1;
+    let (bomb_used, destroyed) = &mut events;
+    bomb_used.send(BombUsed);
+    for (entity, transform, enemy) in target_query.iter() {
+        commands.entity(entity).despawn();
+        if enemy.is_some() {
+            score.0 += 1;
+            destroyed.send(EnemyDestroyed(transform.translation));
+        }
+    }
+}
+
+fn create_enemy_shot(
+    mut game_rng: ResMut<GameRng>,
+    mut query: Query<&mut Transform, With<Enemy>>,
+    mut commands: Commands,
+) {
     // 一定周期でショットを生成
-    if rand::thread_rng().gen_range(0, 100) != 0 {
+    if game_rng.rng().gen_range(0, 100) != 0 {
         return;
     }
     for enemy_transform in query.iter_mut() {
-        // shot
-        commands.spawn((
-            SpriteBundle {
-                sprite: Sprite {
-                    rect: Some(Rect {
-                        min: Vec2::new(0.0, 0.0),
-                        max: Vec2::new(10.0, 10.0),
-                    }),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: enemy_transform.translation,
-                    ..default()
-                },
-                ..default()
-            },
-            EnemyShot,
-            Collider,
-        ));
+        spawn_enemy_shot(
+            &mut commands,
+            enemy_transform.translation,
+            Vec2::NEG_Y * 500.0,
+        );
     }
 }
 
 fn move_player(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Transform, With<Player>>,
+    actions: Actions,
+    mut query: Query<(&mut Transform, &Player)>,
     time_step: Res<FixedTime>,
 ) {
-    let mut player_transform = query.single_mut();
-    let mut direction = Vec3::ZERO;
-    let speed = 800.0;
-
-    if keyboard_input.pressed(KeyCode::Left) {
-        direction -= Vec3::X;
-    }
+    for (mut player_transform, player) in query.iter_mut() {
+        let direction = actions.direction(player.0).extend(0.0);
+        let mut speed = 800.0;
 
-    if keyboard_input.pressed(KeyCode::Right) {
-        direction += Vec3::X;
-    }
-
-    if keyboard_input.pressed(KeyCode::Up) {
-        direction += Vec3::Y;
-    }
+        // 低速移動
+        if actions.pressed(player.0, Action::Focus) {
+            speed *= 0.5;
+        }
 
-    if keyboard_input.pressed(KeyCode::Down) {
-        direction -= Vec3::Y;
+        player_transform.translation += time_step.period.as_secs_f32() * direction * speed;
+        // 画面外に出ないようにする
+        let half_size = Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT) / 2.0;
+        let position = player_transform
+            .translation
+            .truncate()
+            .clamp(-half_size, half_size);
+        player_transform.translation = position.extend(player_transform.translation.z);
     }
-
-    player_transform.translation += time_step.period.as_secs_f32() * direction * speed;
 }
 
-fn move_shot(mut query: Query<&mut Transform, With<PlayerShot>>, time_step: Res<FixedTime>) {
-    for mut shot_transform in query.iter_mut() {
+fn move_shot(
+    mut query: Query<(Entity, &mut Transform), With<PlayerShot>>,
+    time_step: Res<FixedTime>,
+    mut commands: Commands,
+) {
+    for (entity, mut shot_transform) in query.iter_mut() {
         shot_transform.translation += time_step.period.as_secs_f32() * Vec3::Y * 1000.0;
+        if is_offscreen(shot_transform.translation) {
+            commands.entity(entity).despawn();
+        }
     }
 }
 
-fn move_enemy_shot(mut query: Query<&mut Transform, With<EnemyShot>>, time_step: Res<FixedTime>) {
-    for mut shot_transform in query.iter_mut() {
-        shot_transform.translation -= time_step.period.as_secs_f32() * Vec3::Y * 500.0;
+fn move_enemy_shot(
+    mut query: Query<(Entity, &ShotVelocity, &mut Transform), With<EnemyShot>>,
+    time_step: Res<FixedTime>,
+    mut commands: Commands,
+) {
+    for (entity, velocity, mut shot_transform) in query.iter_mut() {
+        shot_transform.translation += (time_step.period.as_secs_f32() * velocity.0).extend(0.0);
+        if is_offscreen(shot_transform.translation) {
+            commands.entity(entity).despawn();
+        }
     }
 }
 
-fn move_enemy(mut query: Query<&mut Transform, With<Enemy>>, time_step: Res<FixedTime>) {
-    for mut enemy_transform in query.iter_mut() {
+fn move_enemy(
+    mut query: Query<(Entity, &mut Transform), With<Enemy>>,
+    time_step: Res<FixedTime>,
+    mut commands: Commands,
+) {
+    for (entity, mut enemy_transform) in query.iter_mut() {
         // 波状に下へ移動
         enemy_transform.translation -= time_step.period.as_secs_f32() * Vec3::Y * 100.0;
+        if is_offscreen(enemy_transform.translation) {
+            commands.entity(entity).despawn();
+        }
     }
 }
 
-fn show_score(
-    score: Res<Score>,
-    mut score_ui_query: Query<&mut Children, With<ScoreUI>>,
+// やり直すときは状態を抜けないので、プレイ中のエンティティをここで消す
+pub fn delete_playing(
+    scoped_query: Query<(Entity, &StateScoped<GameState>)>,
     mut commands: Commands,
 ) {
-    // update score ui
-    let score_text: String = score.0.to_string();
-    for children in score_ui_query.iter_mut() {
-        for child in children.iter() {
-            let mut text = Text::default();
-            text.sections = vec![
-                TextSection {
-                    value: "Score: ".to_string(),
-                    style: TextStyle {
-                        font_size: 50.0,
-                        color: Color::BLACK,
-                        ..default()
-                    },
-                },
-                TextSection {
-                    value: score_text.clone(),
-                    style: TextStyle {
-                        font_size: 50.0,
-                        color: Color::BLACK,
-                        ..default()
-                    },
-                },
-            ];
-            commands.entity(*child).insert(text);
+    for (entity, scoped) in scoped_query.iter() {
+        if scoped.0 == GameState::Playing {
+            commands.entity(entity).despawn_recursive();
         }
     }
 }
 
-fn check_for_collisions(
+// 自機の周りでこの幅以内を通った敵弾はかすりとして数える
+const GRAZE_MARGIN: f32 = 40.0;
+
+fn check_player_shot_collisions(
     mut score: ResMut<Score>,
-    mut player_query: Query<(Entity, &Transform), With<Player>>,
-    mut enemy_query: Query<(Entity, &Transform), With<Enemy>>,
-    mut player_shot_query: Query<(Entity, &Transform), With<PlayerShot>>,
-    mut enemy_shot_query: Query<(Entity, &Transform), With<EnemyShot>>,
-    mut next_state: ResMut<NextState<GameState>>,
+    mut enemy_query: Query<(Entity, &Transform, &mut EnemyHealth), With<Enemy>>,
+    player_shot_query: Query<(Entity, &Transform), With<PlayerShot>>,
+    mut shot_hit: EventWriter<PlayerShotHit>,
+    mut destroyed: EventWriter<EnemyDestroyed>,
     mut commands: Commands,
 ) {
-    // check for player shot collisions
-    for (_player_shot_entity, player_shot_transform) in player_shot_query.iter_mut() {
-        for (enemy_entity, enemy_transform) in enemy_query.iter_mut() {
+    for (player_shot_entity, player_shot_transform) in player_shot_query.iter() {
+        for (enemy_entity, enemy_transform, mut health) in enemy_query.iter_mut() {
+            if health.0 == 0 {
+                continue;
+            }
             let collision = collide(
                 player_shot_transform.translation,
                 Vec2::new(10.0, 10.0),
@@ -266,16 +480,33 @@ fn check_for_collisions(
             );
             if let Some(collision) = collision {
                 debug!("Collision detected: {:?}", collision);
-                // delete enemy
-                commands.entity(enemy_entity).despawn();
-                // update score
-                score.0 += 1;
+                commands.entity(player_shot_entity).despawn();
+                shot_hit.send(PlayerShotHit(player_shot_transform.translation));
+                health.0 -= 1;
+                if health.0 == 0 {
+                    // delete enemy
+                    commands.entity(enemy_entity).despawn();
+                    // update score
+                    score.0 += 1;
+                    destroyed.send(EnemyDestroyed(enemy_transform.translation));
+                }
+                break;
             }
         }
     }
-    // check for enemy shot collisions
-    for (_enemy_shot_entity, enemy_shot_transform) in enemy_shot_query.iter_mut() {
-        for (_player_entity, player_transform) in player_query.iter_mut() {
+}
+
+// まだかすっていない敵弾
+type UngrazedEnemyShot = (With<EnemyShot>, Without<Grazed>);
+
+fn check_enemy_shot_collisions(
+    player_query: Query<&Transform, With<Player>>,
+    enemy_shot_query: Query<(Entity, &Transform), UngrazedEnemyShot>,
+    mut grazed: EventWriter<EnemyShotGrazed>,
+    mut commands: Commands,
+) {
+    for (enemy_shot_entity, enemy_shot_transform) in enemy_shot_query.iter() {
+        for player_transform in player_query.iter() {
             let collision = collide(
                 enemy_shot_transform.translation,
                 Vec2::new(10.0, 10.0),
@@ -284,43 +515,184 @@ fn check_for_collisions(
             );
             if let Some(collision) = collision {
                 debug!("Collision detected: {:?}", collision);
+                continue;
+            }
+            let graze = collide(
+                enemy_shot_transform.translation,
+                Vec2::new(10.0, 10.0),
+                player_transform.translation,
+                Vec2::splat(100.0 + GRAZE_MARGIN * 2.0),
+            );
+            if graze.is_some() {
+                commands.entity(enemy_shot_entity).insert(Grazed);
+                grazed.send(EnemyShotGrazed);
+                break;
             }
         }
     }
-    // check for enemy collisions
-    for (_enemy_entity, enemy_transform) in enemy_query.iter_mut() {
-        for (_player_entity, player_transform) in player_query.iter_mut() {
-            let collision = collide(
+}
+
+// 無敵中でない自機
+type VulnerablePlayer = (With<Player>, Without<Invulnerable>);
+
+// 敵に当たった自機はしばらく無敵になる
+fn check_player_hits(
+    player_query: Query<(Entity, &Transform), VulnerablePlayer>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    mut player_hit: EventWriter<PlayerHit>,
+    mut commands: Commands,
+) {
+    for (player_entity, player_transform) in player_query.iter() {
+        let hit_by_enemy = enemy_query.iter().any(|enemy_transform| {
+            collide(
                 enemy_transform.translation,
                 Vec2::new(50.0, 50.0),
                 player_transform.translation,
                 Vec2::new(100.0, 100.0),
-            );
-            if let Some(collision) = collision {
-                debug!("Collision detected: {:?}", collision);
-                next_state.set(GameState::Result);
-            }
+            )
+            .is_some()
+        });
+        if hit_by_enemy {
+            debug!("Player {} hit", player_entity.index());
+            commands
+                .entity(player_entity)
+                .insert(Invulnerable(INVULNERABLE_SECONDS));
+            player_hit.send(PlayerHit(player_transform.translation));
         }
     }
 }
 
+// 無敵の間は点滅させる
+fn tick_invulnerable(
+    time_step: Res<FixedTime>,
+    mut player_query: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+    mut commands: Commands,
+) {
+    for (entity, mut invulnerable, mut visibility) in player_query.iter_mut() {
+        invulnerable.0 -= time_step.period.as_secs_f32();
+        if invulnerable.0 <= 0.0 {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Invulnerable>();
+        } else if (invulnerable.0 * 10.0) as i32 % 2 == 0 {
+            *visibility = Visibility::Hidden;
+        } else {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+// 残機が尽きたらコンティニューするか聞く。できないモードならそのまま終わる
+fn lose_life(
+    mut player_hit: EventReader<PlayerHit>,
+    game_mode: Res<GameMode>,
+    mut lives: ResMut<Lives>,
+    mut score: ResMut<Score>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    for _ in player_hit.iter() {
+        // 時間制のモードでは残機の代わりにスコアが減る
+        if game_mode.time_limit().is_some() {
+            score.0 = score.0.saturating_sub(HIT_PENALTY);
+            continue;
+        }
+        // 練習では残機が減らない
+        if *game_mode == GameMode::Practice {
+            continue;
+        }
+        lives.0 = lives.0.saturating_sub(1);
+        if lives.0 > 0 {
+            continue;
+        }
+        if game_mode.allows_continue() {
+            next_pause_state.set(PauseState::Continue);
+        } else {
+            next_state.set(GameState::NameEntry);
+        }
+    }
+}
+
+// スコアはコンティニュー回数から数え直す (アーケードと同じく一の位が回数になる)
+fn continue_game(
+    player_query: Query<Entity, With<Player>>,
+    mut continues: ResMut<Continues>,
+    mut score: ResMut<Score>,
+    (mut lives, mut bombs): (ResMut<Lives>, ResMut<Bombs>),
+    mut run_stats: ResMut<RunStats>,
+    mut commands: Commands,
+) {
+    continues.0 += 1;
+    run_stats.best_credit_score = run_stats.best_credit_score.max(score.0);
+    // アーケードと同じく、コンティニューしたらスコアは数え直して一の位に回数を出す
+    score.0 = continues.0;
+    lives.0 = 3;
+    bombs.0 = 3;
+    // 再開した直後に敵に当たらないようにする
+    for player in player_query.iter() {
+        commands
+            .entity(player)
+            .insert(Invulnerable(INVULNERABLE_SECONDS));
+    }
+}
+
 impl Plugin for GamePlayingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Playing), setup)
+        app.add_event::<RestartPlaying>()
+            .add_event::<PlayerShotFired>()
+            .add_event::<PlayerShotHit>()
+            .add_event::<EnemyDestroyed>()
+            .add_event::<EnemyShotGrazed>()
+            .add_event::<BombUsed>()
+            .add_event::<PlayerHit>()
+            .add_event::<ContinueGame>()
+            .add_systems(OnEnter(GameState::Playing), setup)
             .add_systems(
                 FixedUpdate,
                 (
                     move_player,
                     move_shot,
                     create_player_shot,
-                    create_enemy,
+                    use_bomb,
                     create_enemy_shot,
                     move_enemy,
                     move_enemy_shot,
-                    check_for_collisions,
+                    check_player_shot_collisions,
+                    check_enemy_shot_collisions,
+                    check_player_hits,
+                    tick_invulnerable,
+                )
+                    .in_set(GameplaySet),
+            )
+            .configure_set(
+                FixedUpdate,
+                GameplaySet
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(not_hit_stopped),
+            )
+            .add_systems(
+                Update,
+                (
+                    join_second_player,
+                    (request_shot, request_bomb).run_if(in_state(PauseState::Running)),
+                    lose_life,
+                    continue_game.run_if(on_event::<ContinueGame>()),
                 )
                     .run_if(in_state(GameState::Playing)),
             )
-            .add_systems(Update, show_score.run_if(in_state(GameState::Playing)));
+            .configure_set(
+                Update,
+                RestartSet
+                    .after(delete_playing)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(on_event::<RestartPlaying>()),
+            )
+            .add_systems(
+                Update,
+                delete_playing
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(on_event::<RestartPlaying>()),
+            )
+            .add_systems(Update, setup.in_set(RestartSet));
     }
 }
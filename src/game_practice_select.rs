@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::{GameState, Practice},
+    game_playing::MAX_POWER,
+    locale::{switch_language, Locale, Localized},
+    menu::{spawn_menu, MenuActivated, MenuAdjusted, MenuCancelled, MenuLayout},
+    stage::STAGES,
+    state_scoped::StateScoped,
+};
+
+pub struct GamePracticeSelectPlugin;
+
+#[derive(Component, Debug, Clone, Copy)]
+enum PracticeMenu {
+    Target,
+    Power,
+    Start,
+    Back,
+}
+
+impl PracticeMenu {
+    const ALL: [PracticeMenu; 4] = [
+        PracticeMenu::Target,
+        PracticeMenu::Power,
+        PracticeMenu::Start,
+        PracticeMenu::Back,
+    ];
+}
+
+// 選べる開始地点。各ステージの最初と、そのボスの段階ごと
+fn practice_targets() -> Vec<(usize, Option<usize>)> {
+    STAGES
+        .iter()
+        .enumerate()
+        .flat_map(|(index, stage_data)| {
+            let stage = index + 1;
+            std::iter::once((stage, None))
+                .chain((0..stage_data.boss.phases.len()).map(move |phase| (stage, Some(phase))))
+        })
+        .collect()
+}
+
+fn practice_label(practice: &Practice, locale: &Locale, item: PracticeMenu) -> String {
+    match item {
+        PracticeMenu::Target => {
+            let target = match practice.boss_phase {
+                Some(phase) => {
+                    locale.format("practice.boss_phase", &[&practice.stage, &(phase + 1)])
+                }
+                None => locale.format("practice.stage", &[&practice.stage]),
+            };
+            locale.format("practice.target", &[&target])
+        }
+        PracticeMenu::Power => locale.format("practice.power", &[&practice.power, &MAX_POWER]),
+        PracticeMenu::Start => locale.get("practice.start"),
+        PracticeMenu::Back => locale.get("practice.back"),
+    }
+}
+
+// 値を持つ項目を delta の向きに変える
+fn adjust_practice(practice: &mut Practice, item: PracticeMenu, delta: i32) {
+    match item {
+        PracticeMenu::Target => {
+            let targets = practice_targets();
+            let index = targets
+                .iter()
+                .position(|target| *target == (practice.stage, practice.boss_phase))
+                .unwrap_or(0);
+            let index = (index as i32 + delta).rem_euclid(targets.len() as i32) as usize;
+            (practice.stage, practice.boss_phase) = targets[index];
+        }
+        PracticeMenu::Power => {
+            practice.power = practice
+                .power
+                .saturating_add_signed(delta as isize)
+                .min(MAX_POWER);
+        }
+        PracticeMenu::Start | PracticeMenu::Back => {}
+    }
+}
+
+fn practice_select_menu(practice: Res<Practice>, locale: Res<Locale>, mut commands: Commands) {
+    let labels: Vec<(PracticeMenu, String)> = PracticeMenu::ALL
+        .into_iter()
+        .map(|item| (item, practice_label(&practice, &locale, item)))
+        .collect();
+    let items: Vec<(PracticeMenu, &str)> = labels
+        .iter()
+        .map(|(item, label)| (*item, label.as_str()))
+        .collect();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GameState::PracticeSelect),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 50.0,
+                        color: Color::BLACK,
+                        ..default()
+                    },
+                ),
+                Localized("practice.title"),
+            ));
+            spawn_menu(parent, MenuLayout::List, &items);
+        });
+}
+
+fn input_practice_select_menu(
+    mut activated: EventReader<MenuActivated>,
+    mut adjusted: EventReader<MenuAdjusted>,
+    mut cancelled: EventReader<MenuCancelled>,
+    item_query: Query<&PracticeMenu>,
+    mut practice: ResMut<Practice>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // 決定は値を一つ進め、左右は値を増減する
+    for event in activated.iter() {
+        let Ok(item) = item_query.get(event.0) else {
+            continue;
+        };
+        match item {
+            PracticeMenu::Start => next_state.set(GameState::Playing),
+            PracticeMenu::Back => next_state.set(GameState::ModeSelect),
+            _ => adjust_practice(&mut practice, *item, 1),
+        }
+    }
+    for event in adjusted.iter() {
+        if let Ok(item) = item_query.get(event.0) {
+            adjust_practice(&mut practice, *item, event.1);
+        }
+    }
+    if cancelled.iter().any(|event| item_query.contains(event.0)) {
+        next_state.set(GameState::ModeSelect);
+    }
+}
+
+fn update_practice_labels(
+    practice: Res<Practice>,
+    locale: Res<Locale>,
+    item_query: Query<(&PracticeMenu, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !practice.is_changed() && !locale.is_changed() {
+        return;
+    }
+    for (item, children) in item_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = practice_label(&practice, &locale, *item);
+            }
+        }
+    }
+}
+
+impl Plugin for GamePracticeSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::PracticeSelect), practice_select_menu)
+            .add_systems(
+                Update,
+                (input_practice_select_menu, update_practice_labels)
+                    .chain()
+                    .after(switch_language)
+                    .run_if(in_state(GameState::PracticeSelect)),
+            );
+    }
+}
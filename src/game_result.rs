@@ -1,11 +1,16 @@
 use bevy::prelude::*;
 
 use crate::{
-    game::GameState,
-    game_playing::{Enemy, EnemyShot, Player, PlayerShot},
+    action::{Action, Actions},
+    game::{GameMode, GameState},
+    game_playing::Score,
+    locale::{Locale, Localized},
+    menu::{spawn_localized_menu, MenuActivated, MenuLayout},
+    run_stats::RunStats,
+    state_scoped::StateScoped,
 };
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Copy)]
 enum ResultMenu {
     Restart,
     BackToTitle,
@@ -13,55 +18,74 @@ enum ResultMenu {
 
 pub struct GameResultPlugin;
 
-fn delete_all(
-    mut camera_query: Query<(Entity, &Transform), With<Camera>>,
-    mut player_query: Query<(Entity, &Transform), With<Player>>,
-    mut enemy_query: Query<(Entity, &Transform), With<Enemy>>,
-    mut player_shot_query: Query<(Entity, &Transform), With<PlayerShot>>,
-    mut enemy_shot_query: Query<(Entity, &Transform), With<EnemyShot>>,
-    mut commands: Commands,
-) {
-    for (camera_entity, _camera_transform) in camera_query.iter_mut() {
-        commands.entity(camera_entity).despawn();
-    }
-    for (player_entity, _player_transform) in player_query.iter_mut() {
-        commands.entity(player_entity).despawn();
-    }
-    for (enemy_entity, _enemy_transform) in enemy_query.iter_mut() {
-        commands.entity(enemy_entity).despawn();
-    }
-    for (player_shot_entity, _player_shot_transform) in player_shot_query.iter_mut() {
-        commands.entity(player_shot_entity).despawn();
-    }
-    for (enemy_shot_entity, _enemy_shot_transform) in enemy_shot_query.iter_mut() {
-        commands.entity(enemy_shot_entity).despawn();
+fn continue_from_result(mut next_state: ResMut<NextState<GameState>>, actions: Actions) {
+    // ショットでもすぐにやり直せる。決定はメニューに任せる
+    if actions.any_just_pressed(Action::Shoot) {
+        next_state.set(GameState::Playing);
     }
 }
 
-fn continue_from_result(
-    mut next_state: ResMut<NextState<GameState>>,
-    keyboard_input: Res<Input<KeyCode>>,
-) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        next_state.set(GameState::Playing);
+fn stats_lines(
+    locale: &Locale,
+    game_mode: GameMode,
+    score: &Score,
+    run_stats: &RunStats,
+) -> Vec<String> {
+    // Survival では生き残った時間で、デイリーチャレンジはその日の記録と比べる
+    let value = run_stats.ranking_value(game_mode, score.0);
+    let (new_record_key, high_score_key) = match game_mode {
+        GameMode::Daily => ("result.daily_new_record", "result.daily_best"),
+        _ => ("result.new_record", "result.high_score"),
+    };
+    let high_score = if value > run_stats.previous_best {
+        locale.format(new_record_key, &[&game_mode.format_ranking(value)])
+    } else {
+        locale.format(
+            high_score_key,
+            &[
+                &game_mode.format_ranking(run_stats.previous_best),
+                &game_mode.format_ranking(run_stats.previous_best - value),
+            ],
+        )
+    };
+    let seconds = run_stats.survival_time;
+    let survival_time = format!("{:02}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0);
+    let accuracy = format!("{:.1}", run_stats.accuracy());
+    let mut lines = vec![locale.format("result.score", &[&score.0])];
+    // 練習は記録しないのでハイスコアも出さない
+    if game_mode.is_ranked() {
+        lines.push(high_score);
     }
+    lines.extend([
+        locale.format("result.enemies_destroyed", &[&run_stats.enemies_destroyed]),
+        locale.format("result.shots", &[&run_stats.shots_fired, &accuracy]),
+        locale.format("result.survival_time", &[&survival_time]),
+        locale.format("result.graze", &[&run_stats.graze, &run_stats.max_chain]),
+    ]);
+    lines
 }
 
-fn result_menu(mut commands: Commands) {
-    // ui camera
-    // commands.spawn(Camera2dBundle::default());
-    commands.spawn(ResultMenu::Restart);
+fn result_menu(
+    locale: Res<Locale>,
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    game_mode: Res<GameMode>,
+    mut commands: Commands,
+) {
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        })
+            StateScoped(GameState::Result),
+        ))
         .with_children(|parent| {
             parent
                 .spawn(NodeBundle {
@@ -74,149 +98,54 @@ fn result_menu(mut commands: Commands) {
                     ..default()
                 })
                 .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "Result",
-                        TextStyle {
-                            font_size: 50.0,
-                            color: Color::BLACK,
-                            ..default()
-                        },
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 50.0,
+                                color: Color::BLACK,
+                                ..default()
+                            },
+                        ),
+                        Localized("result.title"),
                     ));
-                    parent
-                        .spawn(NodeBundle {
-                            style: Style {
-                                flex_direction: FlexDirection::Row,
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
+                    for line in stats_lines(&locale, *game_mode, &score, &run_stats) {
+                        parent.spawn(TextBundle::from_section(
+                            line,
+                            TextStyle {
+                                font_size: 30.0,
+                                color: Color::BLACK,
                                 ..default()
                             },
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent
-                                .spawn(ButtonBundle {
-                                    style: Style {
-                                        width: Val::Px(200.0),
-                                        height: Val::Px(100.0),
-                                        border: UiRect::all(Val::Px(2.0)),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..default()
-                                    },
-                                    ..default()
-                                })
-                                .with_children(|parent| {
-                                    parent.spawn(TextBundle::from_section(
-                                        "Restart",
-                                        TextStyle {
-                                            font_size: 50.0,
-                                            color: Color::BLACK,
-                                            ..default()
-                                        },
-                                    ));
-                                });
-                            parent
-                                .spawn(ButtonBundle {
-                                    style: Style {
-                                        width: Val::Px(200.0),
-                                        height: Val::Px(100.0),
-                                        border: UiRect::all(Val::Px(2.0)),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..default()
-                                    },
-                                    ..default()
-                                })
-                                .with_children(|parent| {
-                                    parent.spawn(TextBundle::from_section(
-                                        "Back to Title",
-                                        TextStyle {
-                                            font_size: 50.0,
-                                            color: Color::BLACK,
-                                            ..default()
-                                        },
-                                    ));
-                                });
-                        });
+                        ));
+                    }
+                    spawn_localized_menu(
+                        parent,
+                        MenuLayout::Horizontal,
+                        &[
+                            (ResultMenu::Restart, "menu.restart"),
+                            (ResultMenu::BackToTitle, "menu.back_to_title"),
+                        ],
+                    );
                 });
         });
 }
 
-fn update_result_menu(
-    mut menu_button_query: Query<(&mut BorderColor, &Children), With<Button>>,
-    text_query: Query<&Text>,
-    result_menu_query: Query<&ResultMenu>,
-) {
-    let result_menu = result_menu_query.get_single();
-    if result_menu.is_err() {
-        return;
-    }
-    match result_menu.unwrap() {
-        ResultMenu::Restart => {
-            for (mut border_color, children) in menu_button_query.iter_mut() {
-                for child in children.iter() {
-                    let text = text_query.get(*child).unwrap();
-                    if text.sections.get(0).unwrap().value.as_str() == "Restart" {
-                        border_color.0 = Color::RED;
-                    } else {
-                        border_color.0 = Color::BLACK;
-                    }
-                }
-            }
-        }
-        ResultMenu::BackToTitle => {
-            for (mut border_color, children) in menu_button_query.iter_mut() {
-                for child in children.iter() {
-                    let text = text_query.get(*child).unwrap();
-                    if text.sections.get(0).unwrap().value.as_str() == "Back to Title" {
-                        border_color.0 = Color::RED;
-                    } else {
-                        border_color.0 = Color::BLACK;
-                    }
-                }
-            }
-        }
-    }
-}
-
-fn delete_result_menu(
-    mut result_menu_query: Query<Entity, With<ResultMenu>>,
-    mut menu_query: Query<Entity, With<Node>>,
-    mut commands: Commands,
-) {
-    // delete result menu
-    for result_menu in result_menu_query.iter_mut() {
-        commands.entity(result_menu).despawn();
-    }
-    for menu in menu_query.iter_mut() {
-        commands.entity(menu).despawn();
-    }
-}
-
 fn input_result_menu(
-    keyboard_input: ResMut<Input<KeyCode>>,
-    mut result_menu_query: Query<&mut ResultMenu>,
+    mut activated: EventReader<MenuActivated>,
+    result_menu_query: Query<&ResultMenu>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Left) {
-        for mut result_menu in result_menu_query.iter_mut() {
-            *result_menu = ResultMenu::Restart;
-        }
-    }
-    if keyboard_input.just_pressed(KeyCode::Right) {
-        for mut result_menu in result_menu_query.iter_mut() {
-            *result_menu = ResultMenu::BackToTitle;
-        }
-    }
-    if keyboard_input.just_pressed(KeyCode::Return) {
-        for result_menu in result_menu_query.iter_mut() {
-            match *result_menu {
-                ResultMenu::Restart => {
-                    next_state.set(GameState::Playing);
-                }
-                ResultMenu::BackToTitle => {
-                    next_state.set(GameState::Start);
-                }
+    for event in activated.iter() {
+        let Ok(result_menu) = result_menu_query.get(event.0) else {
+            continue;
+        };
+        match result_menu {
+            ResultMenu::Restart => {
+                next_state.set(GameState::Playing);
+            }
+            ResultMenu::BackToTitle => {
+                next_state.set(GameState::Start);
             }
         }
     }
@@ -227,9 +156,7 @@ impl Plugin for GameResultPlugin {
         app.add_systems(OnEnter(GameState::Result), result_menu)
             .add_systems(
                 Update,
-                (continue_from_result, update_result_menu, input_result_menu)
-                    .run_if(in_state(GameState::Result)),
-            )
-            .add_systems(OnExit(GameState::Result), (delete_all, delete_result_menu));
+                (continue_from_result, input_result_menu).run_if(in_state(GameState::Result)),
+            );
     }
 }
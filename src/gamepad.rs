@@ -0,0 +1,52 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+
+pub struct GamepadInputPlugin;
+
+// プレイヤー番号ごとに割り当てたパッド (0: 1P, 1: 2P)
+#[derive(Resource, Debug, Default)]
+pub struct PlayerPads([Option<Gamepad>; 2]);
+
+impl PlayerPads {
+    pub fn get(&self, player: usize) -> Option<Gamepad> {
+        self.0.get(player).copied().flatten()
+    }
+}
+
+fn assign_pads(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    mut player_pads: ResMut<PlayerPads>,
+) {
+    for event in connection_events.iter() {
+        match event.connection {
+            GamepadConnection::Connected(_) => {
+                if player_pads.0.contains(&Some(event.gamepad)) {
+                    continue;
+                }
+                if let Some(slot) = player_pads.0.iter().position(Option::is_none) {
+                    info!(
+                        "Gamepad {:?} assigned to player {}",
+                        event.gamepad,
+                        slot + 1
+                    );
+                    player_pads.0[slot] = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                for slot in player_pads.0.iter_mut() {
+                    if *slot == Some(event.gamepad) {
+                        info!("Gamepad {:?} disconnected", event.gamepad);
+                        *slot = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Plugin for GamepadInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerPads>()
+            .add_systems(Update, assign_pads);
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{game::GameMode, storage};
+
+pub struct HighScorePlugin;
+
+const HIGH_SCORES_FILE: &str = "highscores.cfg";
+
+// モードごとに残す件数
+const TABLE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: usize,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct HighScores(HashMap<GameMode, Vec<HighScoreEntry>>);
+
+impl HighScores {
+    pub fn entries(&self, mode: GameMode) -> &[HighScoreEntry] {
+        self.0.get(&mode).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn best(&self, mode: GameMode) -> usize {
+        self.entries(mode)
+            .first()
+            .map(|entry| entry.score)
+            .unwrap_or(0)
+    }
+
+    // ランキングに入るスコアか
+    pub fn qualifies(&self, mode: GameMode, score: usize) -> bool {
+        let entries = self.entries(mode);
+        score > 0 && (entries.len() < TABLE_SIZE || entries.iter().any(|entry| score > entry.score))
+    }
+
+    // 登録して順位 (0 始まり) を返す
+    pub fn insert(&mut self, mode: GameMode, name: String, score: usize) -> usize {
+        let entries = self.0.entry(mode).or_default();
+        let rank = entries
+            .iter()
+            .position(|entry| score > entry.score)
+            .unwrap_or(entries.len());
+        entries.insert(rank, HighScoreEntry { name, score });
+        entries.truncate(TABLE_SIZE);
+        rank
+    }
+
+    // 1 行 1 件で `モード = 名前 スコア` の形で保存する
+    fn load() -> Self {
+        let mut high_scores = Self::default();
+        for (mode_name, value) in storage::load_entries(HIGH_SCORES_FILE).unwrap_or_default() {
+            let Some(mode) = GameMode::from_name(&mode_name) else {
+                continue;
+            };
+            let Some((name, score)) = value.rsplit_once(' ') else {
+                continue;
+            };
+            let Ok(score) = score.parse() else {
+                continue;
+            };
+            high_scores.insert(mode, name.trim().to_string(), score);
+        }
+        high_scores
+    }
+
+    pub fn save(&self) {
+        let mut entries = Vec::new();
+        for (mode, table) in self.0.iter() {
+            for entry in table {
+                entries.push((
+                    mode.name().to_string(),
+                    format!("{} {}", entry.name, entry.score),
+                ));
+            }
+        }
+        if let Err(err) = storage::save_entries(HIGH_SCORES_FILE, &entries) {
+            warn!("Failed to save high scores: {}", err);
+        }
+    }
+}
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HighScores::load());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(high_scores: &HighScores) -> Vec<usize> {
+        high_scores
+            .entries(GameMode::Arcade)
+            .iter()
+            .map(|entry| entry.score)
+            .collect()
+    }
+
+    #[test]
+    fn insert_keeps_descending_order() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.insert(GameMode::Arcade, "AAA".into(), 200), 0);
+        assert_eq!(high_scores.insert(GameMode::Arcade, "BBB".into(), 500), 0);
+        assert_eq!(high_scores.insert(GameMode::Arcade, "CCC".into(), 300), 1);
+        assert_eq!(scores(&high_scores), [500, 300, 200]);
+        assert_eq!(high_scores.best(GameMode::Arcade), 500);
+    }
+
+    #[test]
+    fn insert_truncates_to_table_size() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=TABLE_SIZE + 2 {
+            high_scores.insert(GameMode::Arcade, "AAA".into(), score * 100);
+        }
+        assert_eq!(scores(&high_scores).len(), TABLE_SIZE);
+        assert_eq!(scores(&high_scores).last(), Some(&300));
+    }
+
+    // 同点は先に登録した方が上に残る
+    #[test]
+    fn ties_rank_below_existing_entries() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(GameMode::Arcade, "AAA".into(), 100);
+        assert_eq!(high_scores.insert(GameMode::Arcade, "BBB".into(), 100), 1);
+        assert_eq!(high_scores.entries(GameMode::Arcade)[0].name, "AAA");
+    }
+
+    #[test]
+    fn qualifies_needs_a_free_slot_or_a_higher_score() {
+        let mut high_scores = HighScores::default();
+        assert!(!high_scores.qualifies(GameMode::Arcade, 0));
+        assert!(high_scores.qualifies(GameMode::Arcade, 1));
+        for score in 1..=TABLE_SIZE {
+            high_scores.insert(GameMode::Arcade, "AAA".into(), score * 100);
+        }
+        assert!(!high_scores.qualifies(GameMode::Arcade, 100));
+        assert!(high_scores.qualifies(GameMode::Arcade, 101));
+        // 他のモードの表とは別に数える
+        assert!(high_scores.qualifies(GameMode::Survival, 1));
+    }
+}
@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+
+use crate::{
+    boss::Boss,
+    camera::HUD_WIDTH,
+    game::{format_time, GameMode, GameState},
+    game_playing::{Bombs, Lives, Power, RestartSet, Score, Stage, MAX_POWER},
+    locale::{Locale, Localized},
+    run_stats::RunStats,
+    state_scoped::StateScoped,
+};
+
+pub struct HudPlugin;
+
+const HUD_BACKGROUND: Color = Color::rgb(0.05, 0.05, 0.12);
+
+// 表示中のスコアが実際のスコアに追いつく速さ。差の割合 (/秒) と最低速度 (点/秒)
+const COUNT_UP_RATE: f32 = 8.0;
+const MIN_COUNT_UP_SPEED: f32 = 20.0;
+
+// HUD に表示しているスコア。実際のスコアまで数えながら増やす
+#[derive(Resource, Debug, Default)]
+struct ShownScore(f32);
+
+// HUD に並べる項目
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum HudField {
+    HiScore,
+    Score,
+    Lives,
+    Bombs,
+    Power,
+    Graze,
+    // 経過時間 (Survival とボスラッシュのみ)
+    Time,
+    // 時間制のモードの残り時間
+    TimeLeft,
+    Stage,
+    BossTimer,
+}
+
+impl HudField {
+    const ALL: [HudField; 10] = [
+        HudField::HiScore,
+        HudField::Score,
+        HudField::Lives,
+        HudField::Bombs,
+        HudField::Power,
+        HudField::Graze,
+        HudField::Time,
+        HudField::TimeLeft,
+        HudField::Stage,
+        HudField::BossTimer,
+    ];
+
+    // 文字列表のキー
+    fn label(&self) -> &'static str {
+        match self {
+            HudField::HiScore => "hud.hi_score",
+            HudField::Score => "hud.score",
+            HudField::Lives => "hud.lives",
+            HudField::Bombs => "hud.bombs",
+            HudField::Power => "hud.power",
+            HudField::Graze => "hud.graze",
+            HudField::Time => "hud.time",
+            HudField::TimeLeft => "hud.time_left",
+            HudField::Stage => "hud.stage",
+            HudField::BossTimer => "hud.boss_timer",
+        }
+    }
+
+    // モードに関係の無い項目は出さない
+    fn shown_in(&self, game_mode: GameMode) -> bool {
+        match self {
+            HudField::Lives => game_mode.time_limit().is_none(),
+            HudField::Time => matches!(game_mode, GameMode::Survival | GameMode::BossRush),
+            HudField::TimeLeft => game_mode.time_limit().is_some(),
+            HudField::Stage | HudField::BossTimer => game_mode.has_stages(),
+            _ => true,
+        }
+    }
+}
+
+// プレイ領域の右側 (ワールドでは黒い帯で隠している所) に重ねて表示する
+fn spawn_hud(game_mode: Res<GameMode>, mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(0.0),
+                    width: Val::Px(HUD_WIDTH),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(30.0)),
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: HUD_BACKGROUND.into(),
+                ..default()
+            },
+            StateScoped(GameState::Playing),
+        ))
+        .with_children(|parent| {
+            for field in HudField::ALL
+                .into_iter()
+                .filter(|field| field.shown_in(*game_mode))
+            {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font_size: 24.0,
+                                    color: Color::GRAY,
+                                    ..default()
+                                },
+                            ),
+                            Localized(field.label()),
+                        ));
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font_size: 40.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            ),
+                            field,
+                        ));
+                    });
+            }
+        });
+}
+
+fn count_up_score(time: Res<Time>, score: Res<Score>, mut shown_score: ResMut<ShownScore>) {
+    let target = score.0 as f32;
+    // やり直しなどでスコアが減ったときはすぐに合わせる
+    if shown_score.0 >= target {
+        if shown_score.0 > target {
+            shown_score.0 = target;
+        }
+        return;
+    }
+    let speed = ((target - shown_score.0) * COUNT_UP_RATE).max(MIN_COUNT_UP_SPEED);
+    shown_score.0 = (shown_score.0 + speed * time.delta_seconds()).min(target);
+}
+
+// 元になる値が変わった項目だけ書き換える
+fn update_hud(
+    (score, shown_score): (Res<Score>, Res<ShownScore>),
+    game_mode: Res<GameMode>,
+    (lives, bombs, power): (Res<Lives>, Res<Bombs>, Res<Power>),
+    (run_stats, stage, locale): (Res<RunStats>, Res<Stage>, Res<Locale>),
+    (boss_query, mut removed_bosses): (Query<Ref<Boss>>, RemovedComponents<Boss>),
+    mut shown_graze: Local<Option<usize>>,
+    mut hud_query: Query<(Ref<HudField>, &mut Text)>,
+) {
+    // RunStats は固定ステップごとに時間が進むので、かすりは数が変わったときだけ書き換える
+    let graze_changed = *shown_graze != Some(run_stats.graze);
+    *shown_graze = Some(run_stats.graze);
+    let boss_changed =
+        boss_query.iter().any(|boss| boss.is_changed()) || removed_bosses.iter().next().is_some();
+    for (field, mut text) in hud_query.iter_mut() {
+        let changed = field.is_added()
+            || match *field {
+                // Survival のハイスコアは生き残った時間で比べる
+                HudField::HiScore => {
+                    score.is_changed()
+                        || (*game_mode == GameMode::Survival && run_stats.is_changed())
+                }
+                HudField::Score => shown_score.is_changed(),
+                HudField::Lives => lives.is_changed(),
+                HudField::Bombs => bombs.is_changed(),
+                HudField::Power => power.is_changed() || locale.is_changed(),
+                HudField::Graze => graze_changed,
+                HudField::Time | HudField::TimeLeft => run_stats.is_changed(),
+                HudField::Stage => stage.is_changed(),
+                HudField::BossTimer => boss_changed,
+            };
+        if !changed {
+            continue;
+        }
+        let value = match *field {
+            // 記録を更新中ならハイスコアも一緒に伸ばす
+            HudField::HiScore => {
+                let value = run_stats.ranking_value(*game_mode, score.0);
+                game_mode.format_ranking(run_stats.previous_best.max(value))
+            }
+            HudField::Score => (shown_score.0 as usize).to_string(),
+            HudField::Lives => "*".repeat(lives.0),
+            HudField::Bombs => "B".repeat(bombs.0),
+            HudField::Power if power.0 >= MAX_POWER => locale.get("hud.power_max"),
+            HudField::Power => format!("{} / {}", power.0, MAX_POWER),
+            HudField::Graze => run_stats.graze.to_string(),
+            HudField::Time => format_time(run_stats.survival_time),
+            HudField::TimeLeft => {
+                let time_limit = game_mode.time_limit().unwrap_or_default();
+                format_time(time_limit - run_stats.survival_time)
+            }
+            HudField::Stage => stage.0.to_string(),
+            HudField::BossTimer => match boss_query.get_single() {
+                Ok(boss) => format!("{:.0}", boss.time_left().ceil()),
+                Err(_) => "--".to_string(),
+            },
+        };
+        // 同じ値なら触らずにおき、文字の並べ直しを起こさない
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShownScore>()
+            .add_systems(OnEnter(GameState::Playing), spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    spawn_hud.in_set(RestartSet),
+                    (count_up_score, update_hud).chain(),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::{
+    action::{bindable_buttons, button_name, is_bindable_key, key_name, Action, InputBindings},
+    game::OverlayState,
+    locale::{Locale, Localized},
+    menu::{spawn_menu, Menu, MenuActivated, MenuCancelled, MenuDisabled, MenuLayout},
+    state_scoped::StateScoped,
+};
+
+pub struct KeyConfigPlugin;
+
+#[derive(Component, Debug, Clone, Copy)]
+enum KeyConfigMenu {
+    Bind(Action),
+    Reset,
+    Back,
+}
+
+#[derive(Component, Debug)]
+struct KeyConfigMessage;
+
+// Esc も割り当てられるので、入力待ちは時間切れでキャンセルする
+const REBIND_TIMEOUT: f32 = 5.0;
+
+// 新しいキーを待っている操作と、待つのをやめる時刻、画面に出すメッセージ
+#[derive(Resource, Debug, Default)]
+struct Rebinding {
+    action: Option<Action>,
+    deadline: f32,
+    message: String,
+}
+
+fn action_name(locale: &Locale, action: Action) -> String {
+    locale.get(&format!("action.{}", action.name()))
+}
+
+fn binding_label(bindings: &InputBindings, locale: &Locale, action: Action) -> String {
+    let binding = bindings.get(action);
+    let keys: Vec<String> = binding.keys.iter().map(|key| key_name(*key)).collect();
+    let buttons: Vec<String> = binding
+        .buttons
+        .iter()
+        .map(|button| button_name(*button))
+        .collect();
+    locale.format(
+        "controls.binding",
+        &[
+            &action_name(locale, action),
+            &keys.join(" "),
+            &buttons.join(" "),
+        ],
+    )
+}
+
+fn key_config_menu(bindings: Res<InputBindings>, locale: Res<Locale>, mut commands: Commands) {
+    let mut labels: Vec<(KeyConfigMenu, String)> = Action::ALL
+        .into_iter()
+        .map(|action| {
+            (
+                KeyConfigMenu::Bind(action),
+                binding_label(&bindings, &locale, action),
+            )
+        })
+        .collect();
+    labels.push((KeyConfigMenu::Reset, locale.get("controls.reset")));
+    labels.push((KeyConfigMenu::Back, locale.get("controls.back")));
+    let items: Vec<(KeyConfigMenu, &str)> = labels
+        .iter()
+        .map(|(item, label)| (*item, label.as_str()))
+        .collect();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+                // 下の画面のボタンをマウスで触れないようにする
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+            StateScoped(OverlayState::KeyConfig),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 50.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("controls.title"),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 30.0,
+                        color: Color::YELLOW,
+                        ..default()
+                    },
+                ),
+                KeyConfigMessage,
+            ));
+            spawn_menu(parent, MenuLayout::List, &items);
+        });
+}
+
+fn input_key_config_menu(
+    mut activated: EventReader<MenuActivated>,
+    mut cancelled: EventReader<MenuCancelled>,
+    item_query: Query<(&KeyConfigMenu, &Parent)>,
+    (time, locale): (Res<Time>, Res<Locale>),
+    (mut bindings, mut rebinding): (ResMut<InputBindings>, ResMut<Rebinding>),
+    mut next_overlay_state: ResMut<NextState<OverlayState>>,
+    mut commands: Commands,
+) {
+    for event in activated.iter() {
+        let Ok((item, parent)) = item_query.get(event.0) else {
+            continue;
+        };
+        match item {
+            KeyConfigMenu::Bind(action) => {
+                rebinding.action = Some(*action);
+                rebinding.deadline = time.elapsed_seconds() + REBIND_TIMEOUT;
+                rebinding.message =
+                    locale.format("controls.prompt", &[&action_name(&locale, *action)]);
+                // 入力を待つ間はメニューを止める
+                commands.entity(parent.get()).insert(MenuDisabled);
+            }
+            KeyConfigMenu::Reset => {
+                *bindings = InputBindings::default();
+                bindings.save();
+                rebinding.message = locale.get("controls.restored");
+            }
+            KeyConfigMenu::Back => {
+                next_overlay_state.set(OverlayState::Options);
+            }
+        }
+    }
+    if cancelled.iter().any(|event| item_query.contains(event.0)) {
+        next_overlay_state.set(OverlayState::Options);
+    }
+}
+
+fn capture_binding(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    (mut bindings, mut rebinding): (ResMut<InputBindings>, ResMut<Rebinding>),
+    (time, locale): (Res<Time>, Res<Locale>),
+    menu_query: Query<Entity, (With<Menu>, With<MenuDisabled>)>,
+    mut commands: Commands,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+    let message = if time.elapsed_seconds() >= rebinding.deadline {
+        locale.get("controls.cancelled")
+    } else {
+        let key = keyboard_input
+            .get_just_pressed()
+            .copied()
+            .find(|key| is_bindable_key(*key));
+        let button = gamepad_buttons
+            .get_just_pressed()
+            .map(|button| button.button_type)
+            .find(|button| bindable_buttons().any(|bindable| bindable == *button));
+        if let Some(key) = key {
+            match bindings.key_conflict(action, key) {
+                Some(other) => locale.format(
+                    "controls.conflict",
+                    &[&key_name(key), &action_name(&locale, other)],
+                ),
+                None => {
+                    bindings.set_key(action, key);
+                    bindings.save();
+                    locale.format(
+                        "controls.assigned",
+                        &[&action_name(&locale, action), &key_name(key)],
+                    )
+                }
+            }
+        } else if let Some(button) = button {
+            match bindings.button_conflict(action, button) {
+                Some(other) => locale.format(
+                    "controls.conflict",
+                    &[&button_name(button), &action_name(&locale, other)],
+                ),
+                None => {
+                    bindings.set_button(action, button);
+                    bindings.save();
+                    locale.format(
+                        "controls.assigned",
+                        &[&action_name(&locale, action), &button_name(button)],
+                    )
+                }
+            }
+        } else {
+            return;
+        }
+    };
+    rebinding.action = None;
+    rebinding.message = message;
+    for menu in menu_query.iter() {
+        commands.entity(menu).remove::<MenuDisabled>();
+    }
+}
+
+fn update_key_config_labels(
+    bindings: Res<InputBindings>,
+    locale: Res<Locale>,
+    item_query: Query<(&KeyConfigMenu, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (item, children) in item_query.iter() {
+        let KeyConfigMenu::Bind(action) = item else {
+            continue;
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = binding_label(&bindings, &locale, *action);
+            }
+        }
+    }
+}
+
+fn update_key_config_message(
+    rebinding: Res<Rebinding>,
+    mut message_query: Query<&mut Text, With<KeyConfigMessage>>,
+) {
+    for mut text in message_query.iter_mut() {
+        text.sections[0].value = rebinding.message.clone();
+    }
+}
+
+fn reset_rebinding(mut rebinding: ResMut<Rebinding>) {
+    *rebinding = Rebinding::default();
+}
+
+impl Plugin for KeyConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(OnEnter(OverlayState::KeyConfig), key_config_menu)
+            .add_systems(
+                Update,
+                (
+                    capture_binding,
+                    input_key_config_menu,
+                    update_key_config_labels.run_if(resource_changed::<InputBindings>()),
+                    update_key_config_message.run_if(resource_changed::<Rebinding>()),
+                )
+                    .chain()
+                    .run_if(in_state(OverlayState::KeyConfig)),
+            )
+            .add_systems(OnExit(OverlayState::KeyConfig), reset_rebinding);
+    }
+}
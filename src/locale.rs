@@ -0,0 +1,161 @@
+use std::{fmt::Display, fs};
+
+use bevy::asset::{FileAssetIo, LoadState};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
+
+use crate::{
+    settings::{Language, Settings},
+    storage,
+};
+
+pub struct LocalePlugin;
+
+// 日本語も表示できるフォント。読めなければ Bevy の組み込みフォントのまま英語で表示する
+const FONT_PATH: &str = "fonts/NotoSansJP-Regular.ttf";
+
+// assets に置いたファイルと同じ内容。ファイルが無くても表示できるように組み込んでおく
+const ENGLISH_STRINGS: &str = include_str!("../assets/locale/en.cfg");
+const JAPANESE_STRINGS: &str = include_str!("../assets/locale/ja.cfg");
+
+fn strings_source(language: Language) -> (&'static str, &'static str) {
+    match language {
+        Language::English => ("en.cfg", ENGLISH_STRINGS),
+        Language::Japanese => ("ja.cfg", JAPANESE_STRINGS),
+    }
+}
+
+// 選択中の言語の文字列表
+#[derive(Resource, Debug)]
+pub struct Locale {
+    language: Language,
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    // 訳の無いキーは英語の文字列で埋める
+    pub fn load(language: Language) -> Self {
+        let mut strings = HashMap::new();
+        for source in [Language::English, language] {
+            let (file_name, embedded) = strings_source(source);
+            let mut text = embedded.to_string();
+            let path = FileAssetIo::get_base_path()
+                .join("assets/locale")
+                .join(file_name);
+            if let Ok(file) = fs::read_to_string(path) {
+                text.push('\n');
+                text.push_str(&file);
+            }
+            strings.extend(storage::parse_entries(&text));
+        }
+        Self { language, strings }
+    }
+
+    // 表に無いキーはそのまま表示して、抜けに気付けるようにする
+    pub fn get(&self, key: &str) -> String {
+        match self.strings.get(key) {
+            Some(value) => value.clone(),
+            None => {
+                warn!("Missing string for {}", key);
+                key.to_string()
+            }
+        }
+    }
+
+    // 文字列中の {} を前から順に args で置き換える
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        let template = self.get(key);
+        let mut pieces = template.split("{}");
+        let mut text = pieces.next().unwrap_or_default().to_string();
+        for (index, piece) in pieces.enumerate() {
+            if let Some(arg) = args.get(index) {
+                text.push_str(&arg.to_string());
+            }
+            text.push_str(piece);
+        }
+        text
+    }
+}
+
+// 文字列表のキー。付けた Text の最初の区切りを言語に合わせて書き換える
+#[derive(Component, Debug)]
+pub struct Localized(pub &'static str);
+
+#[derive(Resource, Debug)]
+pub struct UiFont(Handle<Font>);
+
+fn load_ui_font(asset_server: Res<AssetServer>, mut commands: Commands) {
+    commands.insert_resource(UiFont(asset_server.load(FONT_PATH)));
+}
+
+// 組み込みフォントでは日本語が表示できないので、フォントが読めなければ英語にする。
+// 設定は日本語のまま残すので、フォントを置いて起動し直せば日本語になる
+pub fn switch_language(
+    settings: Res<Settings>,
+    (ui_font, asset_server): (Res<UiFont>, Res<AssetServer>),
+    mut locale: ResMut<Locale>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let language = match asset_server.get_load_state(&ui_font.0) {
+        LoadState::Failed => Language::English,
+        _ => settings.language,
+    };
+    if locale.language == language {
+        return;
+    }
+    *locale = Locale::load(language);
+    for mut window in window_query.iter_mut() {
+        window.title = locale.get("window.title");
+    }
+}
+
+fn localize_texts(locale: Res<Locale>, mut text_query: Query<(Ref<Localized>, &mut Text)>) {
+    for (localized, mut text) in text_query.iter_mut() {
+        if locale.is_changed() || localized.is_added() {
+            text.sections[0].value = locale.get(localized.0);
+        }
+    }
+}
+
+// フォントが読めたら、それ以降に出す文字もすべてこのフォントにする
+fn apply_ui_font(
+    ui_font: Res<UiFont>,
+    asset_server: Res<AssetServer>,
+    mut text_query: Query<&mut Text>,
+    mut font_loaded: Local<bool>,
+    mut font_failed: Local<bool>,
+) {
+    match asset_server.get_load_state(&ui_font.0) {
+        LoadState::Loaded => {}
+        // 置き忘れに気付けるよう一度だけ知らせる
+        LoadState::Failed if !*font_failed => {
+            *font_failed = true;
+            warn!(
+                "Failed to load assets/{}; falling back to English text",
+                FONT_PATH
+            );
+            return;
+        }
+        _ => return,
+    }
+    let newly_loaded = !*font_loaded;
+    *font_loaded = true;
+    for mut text in text_query.iter_mut() {
+        if !newly_loaded && !text.is_added() {
+            continue;
+        }
+        for section in text.sections.iter_mut() {
+            section.style.font = ui_font.0.clone();
+        }
+    }
+}
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_ui_font).add_systems(
+            Update,
+            (switch_language, localize_texts, apply_ui_font).chain(),
+        );
+    }
+}
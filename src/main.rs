@@ -1,12 +1,46 @@
 use bevy::log::{Level, LogPlugin};
 use bevy::prelude::*;
 
+mod action;
+mod audio;
+mod boss;
+mod camera;
+mod daily;
 mod game;
+mod game_continue;
+mod game_feel;
 mod game_start;
+mod game_mode_select;
+mod game_practice_select;
 mod game_playing;
+mod game_pause;
+mod game_name_entry;
 mod game_result;
+mod game_ending;
+mod gamepad;
+mod high_score;
+mod hud;
+mod key_config;
+mod locale;
+mod menu;
+mod options;
+mod particles;
+mod rng;
+mod run_stats;
+mod score_attack;
+mod settings;
+mod sprites;
+mod stage;
+mod starfield;
+mod state_scoped;
+mod storage;
+mod survival;
+mod synth;
 
 fn main() {
+    // ウィンドウの設定に使うので App を作る前に読む
+    let settings = settings::Settings::load();
+    let locale = locale::Locale::load(settings.language);
     App::new()
         .add_plugins((
             DefaultPlugins
@@ -16,20 +50,21 @@ fn main() {
                 })
                 .set(WindowPlugin {
                     primary_window: Some(Window {
-                        title: "test用STGだよ~".to_string(),
+                        title: locale.get("window.title"),
+                        resolution: (1280.0, 720.0).into(),
+                        mode: settings.window_mode(),
                         resize_constraints: WindowResizeConstraints {
-                            min_width: 1280.0,
-                            min_height: 720.0,
-                            max_width: 1280.0,
-                            max_height: 720.0,
+                            min_width: 640.0,
+                            min_height: 360.0,
+                            ..default()
                         },
-                        resizable: false,
                         ..default()
                     }),
                     ..default()
                 }),
             game::GamePlugin,
         ))
-        .add_systems(Update, bevy::window::close_on_esc)
+        .insert_resource(settings)
+        .insert_resource(locale)
         .run();
 }
@@ -0,0 +1,267 @@
+use bevy::prelude::*;
+
+use crate::{
+    action::{Action, Actions},
+    locale::Localized,
+};
+
+pub struct MenuPlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuLayout {
+    Horizontal,
+    Vertical,
+    // 項目数の多い画面向けの小さい縦並び
+    List,
+}
+
+// メニュー本体。子のボタンが並び順どおりに項目になる
+#[derive(Component, Debug)]
+pub struct Menu {
+    layout: MenuLayout,
+    selected: usize,
+}
+
+#[derive(Component, Debug)]
+pub struct MenuItem;
+
+// 付いている間はメニューが入力を受け付けない
+#[derive(Component, Debug)]
+pub struct MenuDisabled;
+
+// 最後に開いたメニューだけがキーとパッドの入力を受け付ける
+#[derive(Resource, Debug, Default)]
+struct MenuFocus(Vec<Entity>);
+
+// 項目が決定された。中身は決定された項目のエンティティ
+#[derive(Event, Debug)]
+pub struct MenuActivated(pub Entity);
+
+// キャンセル操作がされた。中身はそのとき選ばれていた項目のエンティティ
+#[derive(Event, Debug)]
+pub struct MenuCancelled(pub Entity);
+
+// List の項目で左右が押された。中身は選ばれている項目と向き (-1 か 1)
+#[derive(Event, Debug)]
+pub struct MenuAdjusted(pub Entity, pub i32);
+
+// 項目ごとに id となるコンポーネントを付けてメニューを生成する
+pub fn spawn_menu<T: Component + Copy>(
+    parent: &mut ChildBuilder,
+    layout: MenuLayout,
+    items: &[(T, &str)],
+) {
+    let items = items
+        .iter()
+        .map(|(id, label)| (*id, label.to_string(), None))
+        .collect();
+    spawn_menu_items(parent, layout, items);
+}
+
+// 表示名の代わりに文字列表のキーを渡す。言語を切り替えると表示も変わる
+pub fn spawn_localized_menu<T: Component + Copy>(
+    parent: &mut ChildBuilder,
+    layout: MenuLayout,
+    items: &[(T, &'static str)],
+) {
+    let items = items
+        .iter()
+        .map(|(id, key)| (*id, String::new(), Some(Localized(key))))
+        .collect();
+    spawn_menu_items(parent, layout, items);
+}
+
+fn spawn_menu_items<T: Component + Copy>(
+    parent: &mut ChildBuilder,
+    layout: MenuLayout,
+    items: Vec<(T, String, Option<Localized>)>,
+) {
+    parent
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: match layout {
+                        MenuLayout::Horizontal => FlexDirection::Row,
+                        MenuLayout::Vertical | MenuLayout::List => FlexDirection::Column,
+                    },
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            Menu {
+                layout,
+                selected: 0,
+            },
+        ))
+        .with_children(|parent| {
+            let (height, font_size) = match layout {
+                MenuLayout::Horizontal | MenuLayout::Vertical => (100.0, 50.0),
+                MenuLayout::List => (40.0, 30.0),
+            };
+            for (id, label, localized) in items {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                min_width: Val::Px(200.0),
+                                height: Val::Px(height),
+                                padding: UiRect::horizontal(Val::Px(20.0)),
+                                border: UiRect::all(Val::Px(2.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        MenuItem,
+                        id,
+                    ))
+                    .with_children(|parent| {
+                        let mut text = parent.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font_size,
+                                color: Color::BLACK,
+                                ..default()
+                            },
+                        ));
+                        if let Some(localized) = localized {
+                            text.insert(localized);
+                        }
+                    });
+            }
+        });
+}
+
+fn track_menu_focus(
+    added_query: Query<Entity, Added<Menu>>,
+    mut removed_menus: RemovedComponents<Menu>,
+    mut menu_focus: ResMut<MenuFocus>,
+) {
+    for entity in removed_menus.iter() {
+        menu_focus.0.retain(|menu| *menu != entity);
+    }
+    menu_focus.0.extend(added_query.iter());
+}
+
+fn input_menu(
+    actions: Actions,
+    menu_focus: Res<MenuFocus>,
+    mut menu_query: Query<(&mut Menu, &Children), Without<MenuDisabled>>,
+    mut activated: EventWriter<MenuActivated>,
+    mut cancelled: EventWriter<MenuCancelled>,
+    mut adjusted: EventWriter<MenuAdjusted>,
+) {
+    let Some(focused) = menu_focus.0.last() else {
+        return;
+    };
+    let Ok((mut menu, children)) = menu_query.get_mut(*focused) else {
+        return;
+    };
+    let len = children.len();
+    if len == 0 {
+        return;
+    }
+    let (prev, next) = match menu.layout {
+        MenuLayout::Horizontal => (Action::MoveLeft, Action::MoveRight),
+        MenuLayout::Vertical | MenuLayout::List => (Action::MoveUp, Action::MoveDown),
+    };
+    // 端まで行ったら反対側へ回り込む
+    if actions.any_just_pressed(prev) {
+        menu.selected = (menu.selected + len - 1) % len;
+    }
+    if actions.any_just_pressed(next) {
+        menu.selected = (menu.selected + 1) % len;
+    }
+    if menu.layout == MenuLayout::List {
+        if actions.any_just_pressed(Action::MoveLeft) {
+            adjusted.send(MenuAdjusted(children[menu.selected], -1));
+        }
+        if actions.any_just_pressed(Action::MoveRight) {
+            adjusted.send(MenuAdjusted(children[menu.selected], 1));
+        }
+    }
+    if actions.any_just_pressed(Action::Confirm) {
+        activated.send(MenuActivated(children[menu.selected]));
+    }
+    if actions.any_just_pressed(Action::Cancel) {
+        cancelled.send(MenuCancelled(children[menu.selected]));
+    }
+}
+
+// マウスの操作が変わった項目
+type ChangedMenuItem = (Changed<Interaction>, With<MenuItem>);
+
+fn interact_menu(
+    mut interaction_query: Query<
+        (Entity, &Interaction, &Parent, &mut BackgroundColor),
+        ChangedMenuItem,
+    >,
+    mut menu_query: Query<(&mut Menu, &Children), Without<MenuDisabled>>,
+    mut activated: EventWriter<MenuActivated>,
+    mut pressed_item: Local<Option<Entity>>,
+) {
+    for (entity, interaction, parent, mut background_color) in interaction_query.iter_mut() {
+        let Ok((mut menu, children)) = menu_query.get_mut(parent.get()) else {
+            continue;
+        };
+        let Some(index) = children.iter().position(|child| *child == entity) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Hovered => {
+                background_color.0 = Color::WHITE;
+                menu.selected = index;
+                // 押したボタンの上で離したときだけ決定する
+                if *pressed_item == Some(entity) {
+                    *pressed_item = None;
+                    activated.send(MenuActivated(entity));
+                }
+            }
+            Interaction::Pressed => {
+                background_color.0 = Color::GRAY;
+                menu.selected = index;
+                *pressed_item = Some(entity);
+            }
+            Interaction::None => {
+                background_color.0 = Color::WHITE;
+                if *pressed_item == Some(entity) {
+                    *pressed_item = None;
+                }
+            }
+        }
+    }
+}
+
+fn update_menu(
+    menu_query: Query<(&Menu, &Children), Changed<Menu>>,
+    mut item_query: Query<&mut BorderColor, With<MenuItem>>,
+) {
+    for (menu, children) in menu_query.iter() {
+        for (index, child) in children.iter().enumerate() {
+            let Ok(mut border_color) = item_query.get_mut(*child) else {
+                continue;
+            };
+            if index == menu.selected {
+                border_color.0 = Color::RED;
+            } else {
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>()
+            .add_event::<MenuActivated>()
+            .add_event::<MenuCancelled>()
+            .add_event::<MenuAdjusted>()
+            .add_systems(
+                Update,
+                (track_menu_focus, (input_menu, interact_menu), update_menu).chain(),
+            );
+    }
+}
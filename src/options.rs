@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::{
+    game::OverlayState,
+    locale::{switch_language, Locale, Localized},
+    menu::{spawn_menu, MenuActivated, MenuAdjusted, MenuCancelled, MenuLayout},
+    settings::{Language, Settings, MAX_VOLUME},
+    state_scoped::StateScoped,
+};
+
+pub struct OptionsPlugin;
+
+#[derive(Component, Debug, Clone, Copy)]
+enum OptionsMenu {
+    WindowMode,
+    MasterVolume,
+    BgmVolume,
+    SfxVolume,
+    ScreenShake,
+    Language,
+    Controls,
+    Back,
+}
+
+impl OptionsMenu {
+    const ALL: [OptionsMenu; 8] = [
+        OptionsMenu::WindowMode,
+        OptionsMenu::MasterVolume,
+        OptionsMenu::BgmVolume,
+        OptionsMenu::SfxVolume,
+        OptionsMenu::ScreenShake,
+        OptionsMenu::Language,
+        OptionsMenu::Controls,
+        OptionsMenu::Back,
+    ];
+}
+
+fn on_off(locale: &Locale, value: bool) -> String {
+    if value {
+        locale.get("options.on")
+    } else {
+        locale.get("options.off")
+    }
+}
+
+fn option_label(settings: &Settings, locale: &Locale, item: OptionsMenu) -> String {
+    match item {
+        OptionsMenu::WindowMode => {
+            let mode = if settings.fullscreen {
+                locale.get("options.fullscreen")
+            } else {
+                locale.get("options.windowed")
+            };
+            locale.format("options.window_mode", &[&mode])
+        }
+        OptionsMenu::MasterVolume => {
+            locale.format("options.master_volume", &[&settings.master_volume])
+        }
+        OptionsMenu::BgmVolume => locale.format("options.bgm_volume", &[&settings.bgm_volume]),
+        OptionsMenu::SfxVolume => locale.format("options.sfx_volume", &[&settings.sfx_volume]),
+        OptionsMenu::ScreenShake => locale.format(
+            "options.screen_shake",
+            &[&on_off(locale, settings.screen_shake)],
+        ),
+        // 言語名はその言語自身の表記で出す
+        OptionsMenu::Language => {
+            let name = locale.get(&format!("language.{}", settings.language.name()));
+            locale.format("options.language", &[&name])
+        }
+        OptionsMenu::Controls => locale.get("options.controls"),
+        OptionsMenu::Back => locale.get("options.back"),
+    }
+}
+
+// 値を持つ項目を delta の向きに変える。変えられない項目なら false
+fn adjust_option(settings: &mut Settings, item: OptionsMenu, delta: i32) -> bool {
+    let step = |volume: u32| volume.saturating_add_signed(delta).min(MAX_VOLUME);
+    match item {
+        OptionsMenu::WindowMode => settings.fullscreen = !settings.fullscreen,
+        OptionsMenu::MasterVolume => settings.master_volume = step(settings.master_volume),
+        OptionsMenu::BgmVolume => settings.bgm_volume = step(settings.bgm_volume),
+        OptionsMenu::SfxVolume => settings.sfx_volume = step(settings.sfx_volume),
+        OptionsMenu::ScreenShake => settings.screen_shake = !settings.screen_shake,
+        OptionsMenu::Language => {
+            let len = Language::ALL.len();
+            let index = Language::ALL
+                .iter()
+                .position(|language| *language == settings.language)
+                .unwrap_or(0);
+            let index = (index as i32 + delta).rem_euclid(len as i32) as usize;
+            settings.language = Language::ALL[index];
+        }
+        OptionsMenu::Controls | OptionsMenu::Back => return false,
+    }
+    true
+}
+
+fn options_menu(settings: Res<Settings>, locale: Res<Locale>, mut commands: Commands) {
+    let labels: Vec<(OptionsMenu, String)> = OptionsMenu::ALL
+        .into_iter()
+        .map(|item| (item, option_label(&settings, &locale, item)))
+        .collect();
+    let items: Vec<(OptionsMenu, &str)> = labels
+        .iter()
+        .map(|(item, label)| (*item, label.as_str()))
+        .collect();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+                // 下の画面のボタンをマウスで触れないようにする
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+            StateScoped(OverlayState::Options),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 50.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("options.title"),
+            ));
+            spawn_menu(parent, MenuLayout::List, &items);
+        });
+}
+
+fn input_options_menu(
+    mut activated: EventReader<MenuActivated>,
+    mut adjusted: EventReader<MenuAdjusted>,
+    mut cancelled: EventReader<MenuCancelled>,
+    item_query: Query<&OptionsMenu>,
+    mut settings: ResMut<Settings>,
+    mut next_overlay_state: ResMut<NextState<OverlayState>>,
+) {
+    let mut changed = false;
+    // 決定は値を一つ進め、左右は値を増減する
+    for event in activated.iter() {
+        let Ok(item) = item_query.get(event.0) else {
+            continue;
+        };
+        match item {
+            OptionsMenu::Controls => next_overlay_state.set(OverlayState::KeyConfig),
+            OptionsMenu::Back => next_overlay_state.set(OverlayState::None),
+            _ => changed |= adjust_option(&mut settings, *item, 1),
+        }
+    }
+    for event in adjusted.iter() {
+        if let Ok(item) = item_query.get(event.0) {
+            changed |= adjust_option(&mut settings, *item, event.1);
+        }
+    }
+    if changed {
+        settings.save();
+    }
+    if cancelled.iter().any(|event| item_query.contains(event.0)) {
+        next_overlay_state.set(OverlayState::None);
+    }
+}
+
+fn update_option_labels(
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+    item_query: Query<(&OptionsMenu, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (item, children) in item_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = option_label(&settings, &locale, *item);
+            }
+        }
+    }
+}
+
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(OverlayState::Options), options_menu)
+            .add_systems(
+                Update,
+                (
+                    input_options_menu,
+                    update_option_labels.run_if(resource_changed::<Settings>()),
+                )
+                    .chain()
+                    // 言語を変えたときは切り替わった文字列表で書き直す
+                    .after(switch_language)
+                    .run_if(in_state(OverlayState::Options)),
+            );
+    }
+}
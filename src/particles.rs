@@ -0,0 +1,262 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    game::PauseState,
+    game_feel::not_hit_stopped,
+    game_playing::{EnemyDestroyed, PlayerHit, PlayerShotHit},
+};
+
+pub struct ParticlePlugin;
+
+// 同時に存在できる粒の上限。超えた分は出さない
+const MAX_PARTICLES: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleEffect {
+    Explosion,
+    HitSpark,
+    PlayerDeath,
+    EngineTrail,
+}
+
+// 1 回の放出で出す粒の設定
+struct EffectParams {
+    count: usize,
+    // 放出の向きと広がり (ラジアン)
+    direction: Vec2,
+    spread: f32,
+    speed: (f32, f32),
+    lifetime: (f32, f32),
+    start_color: Color,
+    end_color: Color,
+    start_size: f32,
+    end_size: f32,
+    z: f32,
+}
+
+impl ParticleEffect {
+    fn params(&self) -> EffectParams {
+        match self {
+            ParticleEffect::Explosion => EffectParams {
+                count: 24,
+                direction: Vec2::Y,
+                spread: TAU,
+                speed: (100.0, 400.0),
+                lifetime: (0.3, 0.7),
+                start_color: Color::rgb(1.0, 0.9, 0.3),
+                end_color: Color::rgba(0.8, 0.1, 0.0, 0.0),
+                start_size: 12.0,
+                end_size: 4.0,
+                z: 1.0,
+            },
+            ParticleEffect::HitSpark => EffectParams {
+                count: 6,
+                direction: Vec2::NEG_Y,
+                spread: TAU / 4.0,
+                speed: (200.0, 500.0),
+                lifetime: (0.1, 0.2),
+                start_color: Color::WHITE,
+                end_color: Color::rgba(1.0, 1.0, 0.5, 0.0),
+                start_size: 4.0,
+                end_size: 1.0,
+                z: 1.0,
+            },
+            ParticleEffect::PlayerDeath => EffectParams {
+                count: 80,
+                direction: Vec2::Y,
+                spread: TAU,
+                speed: (50.0, 700.0),
+                lifetime: (0.5, 1.5),
+                start_color: Color::WHITE,
+                end_color: Color::rgba(0.3, 0.5, 1.0, 0.0),
+                start_size: 16.0,
+                end_size: 2.0,
+                z: 1.0,
+            },
+            ParticleEffect::EngineTrail => EffectParams {
+                count: 1,
+                direction: Vec2::NEG_Y,
+                spread: TAU / 12.0,
+                speed: (150.0, 250.0),
+                lifetime: (0.15, 0.3),
+                start_color: Color::rgb(0.4, 0.8, 1.0),
+                end_color: Color::rgba(0.1, 0.2, 1.0, 0.0),
+                start_size: 10.0,
+                end_size: 2.0,
+                // 自機より奥に描く
+                z: -0.05,
+            },
+        }
+    }
+}
+
+// 指定した位置に粒を出す
+#[derive(Event, Debug)]
+pub struct EmitParticles {
+    pub effect: ParticleEffect,
+    pub position: Vec2,
+}
+
+// 付けたエンティティから一定間隔で粒を出し続ける
+#[derive(Component, Debug)]
+pub struct ParticleEmitter {
+    effect: ParticleEffect,
+    offset: Vec2,
+    interval: f32,
+    elapsed: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: ParticleEffect, offset: Vec2, interval: f32) -> Self {
+        Self {
+            effect,
+            offset,
+            interval,
+            elapsed: 0.0,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+struct Particle {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    start_color: Color,
+    end_color: Color,
+    start_size: f32,
+    end_size: f32,
+}
+
+// ゲーム中の出来事を粒の放出に変える
+fn particles_from_events(
+    mut shot_hit: EventReader<PlayerShotHit>,
+    mut destroyed: EventReader<EnemyDestroyed>,
+    mut player_hit: EventReader<PlayerHit>,
+    mut emit: EventWriter<EmitParticles>,
+) {
+    let events = shot_hit
+        .iter()
+        .map(|event| (ParticleEffect::HitSpark, event.0))
+        .chain(
+            destroyed
+                .iter()
+                .map(|event| (ParticleEffect::Explosion, event.0)),
+        )
+        .chain(
+            player_hit
+                .iter()
+                .map(|event| (ParticleEffect::PlayerDeath, event.0)),
+        );
+    for (effect, translation) in events {
+        emit.send(EmitParticles {
+            effect,
+            position: translation.truncate(),
+        });
+    }
+}
+
+fn tick_emitters(
+    time_step: Res<FixedTime>,
+    mut emitter_query: Query<(&mut ParticleEmitter, &Transform)>,
+    mut emit: EventWriter<EmitParticles>,
+) {
+    let delta = time_step.period.as_secs_f32();
+    for (mut emitter, transform) in emitter_query.iter_mut() {
+        emitter.elapsed += delta;
+        while emitter.elapsed >= emitter.interval {
+            emitter.elapsed -= emitter.interval;
+            emit.send(EmitParticles {
+                effect: emitter.effect,
+                position: transform.translation.truncate() + emitter.offset,
+            });
+        }
+    }
+}
+
+fn spawn_particles(
+    mut emit: EventReader<EmitParticles>,
+    particle_query: Query<(), With<Particle>>,
+    mut commands: Commands,
+) {
+    let mut rng = rand::thread_rng();
+    let mut live = particle_query.iter().count();
+    for event in emit.iter() {
+        let params = event.effect.params();
+        let count = params.count.min(MAX_PARTICLES.saturating_sub(live));
+        live += count;
+        for _ in 0..count {
+            let angle = rng.gen_range(-0.5_f32, 0.5) * params.spread;
+            let speed = rng.gen_range(params.speed.0, params.speed.1);
+            let velocity = Vec2::from_angle(angle).rotate(params.direction) * speed;
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: params.start_color,
+                        custom_size: Some(Vec2::splat(params.start_size)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(event.position.extend(params.z)),
+                    ..default()
+                },
+                Particle {
+                    velocity,
+                    age: 0.0,
+                    lifetime: rng.gen_range(params.lifetime.0, params.lifetime.1),
+                    start_color: params.start_color,
+                    end_color: params.end_color,
+                    start_size: params.start_size,
+                    end_size: params.end_size,
+                },
+            ));
+        }
+    }
+}
+
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let from = from.as_rgba_f32();
+    let to = to.as_rgba_f32();
+    let mut rgba = [0.0; 4];
+    for (index, value) in rgba.iter_mut().enumerate() {
+        *value = from[index] + (to[index] - from[index]) * t;
+    }
+    Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3])
+}
+
+// 粒は画面の状態をまたいでも寿命が来るまで動かす
+fn update_particles(
+    time_step: Res<FixedTime>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+    mut commands: Commands,
+) {
+    let delta = time_step.period.as_secs_f32();
+    for (entity, mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (particle.velocity * delta).extend(0.0);
+        let t = particle.age / particle.lifetime;
+        sprite.color = lerp_color(particle.start_color, particle.end_color, t);
+        let size = particle.start_size + (particle.end_size - particle.start_size) * t;
+        sprite.custom_size = Some(Vec2::splat(size));
+    }
+}
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EmitParticles>()
+            // プレイ以外の画面でも動かすので GameplaySet には入れない
+            .add_systems(
+                FixedUpdate,
+                (update_particles, tick_emitters)
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(not_hit_stopped),
+            )
+            .add_systems(Update, (particles_from_events, spawn_particles).chain());
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    daily::{daily_seed, DailyDate},
+    game::{GameMode, GameState},
+    game_playing::RestartSet,
+    score_attack::SPAWN_SCRIPT_SEED,
+};
+
+pub struct GameRngPlugin;
+
+// 敵の出現など、展開を決める乱数。シードが同じなら同じ展開になる
+#[derive(Resource, Debug)]
+pub struct GameRng {
+    rng: StdRng,
+    layout: StdRng,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(0)
+    }
+}
+
+impl GameRng {
+    fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            layout: StdRng::seed_from_u64(seed.wrapping_add(1)),
+        }
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    // 敵の出現位置に使う乱数。敵弾の抽選などで進み方が変わらないように別に持つ
+    pub fn layout_rng(&mut self) -> &mut StdRng {
+        &mut self.layout
+    }
+}
+
+// プレイの開始ごとに新しいシードで作り直す。デイリーチャレンジは日付で決まり、
+// スコアアタックは敵弾の抽選も毎回同じになるように決まったシードを使う
+pub fn reseed_game_rng(
+    game_mode: Res<GameMode>,
+    daily_date: Res<DailyDate>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let seed = match *game_mode {
+        GameMode::Daily => daily_seed(&daily_date.0),
+        GameMode::ScoreAttack2 | GameMode::ScoreAttack5 => SPAWN_SCRIPT_SEED,
+        _ => rand::thread_rng().gen(),
+    };
+    info!("Run seed: {}", seed);
+    *game_rng = GameRng::from_seed(seed);
+}
+
+impl Plugin for GameRngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>()
+            .add_systems(OnEnter(GameState::Playing), reseed_game_rng)
+            .add_systems(Update, reseed_game_rng.in_set(RestartSet));
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+use crate::{
+    daily::{DailyBests, DailyDate},
+    game::{GameMode, GameState},
+    game_playing::{
+        EnemyDestroyed, EnemyShotGrazed, GameplaySet, PlayerShotFired, PlayerShotHit, RestartSet,
+    },
+    high_score::HighScores,
+};
+
+pub struct RunStatsPlugin;
+
+// この秒数以内に次の敵を倒すと連鎖が続く
+const CHAIN_WINDOW: f32 = 1.0;
+
+// 1 プレイ分の記録。結果画面で表示する
+#[derive(Resource, Debug, Default)]
+pub struct RunStats {
+    pub enemies_destroyed: usize,
+    pub shots_fired: usize,
+    pub shots_hit: usize,
+    pub survival_time: f32,
+    pub graze: usize,
+    pub max_chain: usize,
+    // 開始時点のハイスコア (GameMode::ranking_value と同じ単位)
+    pub previous_best: usize,
+    // コンティニューする前の回で出した一番高いスコア
+    pub best_credit_score: usize,
+    chain: usize,
+    last_kill_time: Option<f32>,
+}
+
+impl RunStats {
+    // 命中率 (%)
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+        self.shots_hit as f32 / self.shots_fired as f32 * 100.0
+    }
+
+    // ランキングで比べる値。コンティニュー前の回のほうが高ければそちらを使う
+    pub fn ranking_value(&self, game_mode: GameMode, score: usize) -> usize {
+        game_mode.ranking_value(score.max(self.best_credit_score), self.survival_time)
+    }
+}
+
+// デイリーチャレンジはその日の最高スコアと比べる
+pub fn reset_run_stats(
+    high_scores: Res<HighScores>,
+    (daily_bests, daily_date): (Res<DailyBests>, Res<DailyDate>),
+    game_mode: Res<GameMode>,
+    mut commands: Commands,
+) {
+    let previous_best = match *game_mode {
+        GameMode::Daily => daily_bests.best(&daily_date.0),
+        _ => high_scores.best(*game_mode),
+    };
+    commands.insert_resource(RunStats {
+        previous_best,
+        ..default()
+    });
+}
+
+fn tick_survival_time(mut run_stats: ResMut<RunStats>, time_step: Res<FixedTime>) {
+    run_stats.survival_time += time_step.period.as_secs_f32();
+}
+
+fn collect_run_stats(
+    mut shot_fired: EventReader<PlayerShotFired>,
+    mut shot_hit: EventReader<PlayerShotHit>,
+    mut destroyed: EventReader<EnemyDestroyed>,
+    mut grazed: EventReader<EnemyShotGrazed>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    run_stats.shots_fired += shot_fired.iter().count();
+    run_stats.shots_hit += shot_hit.iter().count();
+    run_stats.graze += grazed.iter().count();
+    for _ in destroyed.iter() {
+        let now = run_stats.survival_time;
+        run_stats.enemies_destroyed += 1;
+        run_stats.chain = match run_stats.last_kill_time {
+            Some(last) if now - last <= CHAIN_WINDOW => run_stats.chain + 1,
+            _ => 1,
+        };
+        run_stats.max_chain = run_stats.max_chain.max(run_stats.chain);
+        run_stats.last_kill_time = Some(now);
+    }
+}
+
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(GameState::Playing), reset_run_stats)
+            .add_systems(FixedUpdate, tick_survival_time.in_set(GameplaySet))
+            .add_systems(
+                Update,
+                (reset_run_stats.in_set(RestartSet), collect_run_stats)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    camera::PLAYFIELD_WIDTH,
+    game::{GameMode, GameState},
+    game_playing::{spawn_enemy, GameplaySet, RestartSet},
+    run_stats::RunStats,
+    starfield::ScrollSpeed,
+};
+
+pub struct ScoreAttackPlugin;
+
+// 出現の台本を決めるシード。毎回同じ並びで敵が出る。GameRng もこのシードで作る
+pub const SPAWN_SCRIPT_SEED: u64 = 0x5354_4731;
+
+// 編隊を出す間隔 (秒) と、1 編隊の敵の数の上限
+const FORMATION_INTERVAL: f32 = 1.2;
+const MAX_FORMATION_SIZE: usize = 5;
+const FORMATION_SPACING: f32 = 70.0;
+
+// この秒数ごとに敵の耐久が 1 増える
+const HEALTH_STEP_SECONDS: f32 = 40.0;
+
+const SCROLL_SPEED: f32 = 200.0;
+
+// 出現の台本。GameRng は敵弾の抽選などプレイ内容で進み方が変わるので、別の乱数を持つ
+#[derive(Resource, Debug)]
+struct SpawnScript {
+    rng: StdRng,
+    elapsed: f32,
+}
+
+impl Default for SpawnScript {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(SPAWN_SCRIPT_SEED),
+            elapsed: 0.0,
+        }
+    }
+}
+
+fn score_attack_mode(game_mode: Res<GameMode>) -> bool {
+    game_mode.time_limit().is_some()
+}
+
+fn reset_spawn_script(mut scroll_speed: ResMut<ScrollSpeed>, mut commands: Commands) {
+    scroll_speed.0 = SCROLL_SPEED;
+    commands.insert_resource(SpawnScript::default());
+}
+
+// 横一列の編隊を出す
+fn spawn_formations(
+    time_step: Res<FixedTime>,
+    run_stats: Res<RunStats>,
+    mut script: ResMut<SpawnScript>,
+    mut commands: Commands,
+) {
+    script.elapsed += time_step.period.as_secs_f32();
+    if script.elapsed < FORMATION_INTERVAL {
+        return;
+    }
+    script.elapsed -= FORMATION_INTERVAL;
+    let count = script.rng.gen_range(1, MAX_FORMATION_SIZE + 1);
+    let width = FORMATION_SPACING * (count - 1) as f32;
+    let half_range = (PLAYFIELD_WIDTH - width) / 2.0 - 50.0;
+    let left = script.rng.gen_range(-half_range, half_range) - width / 2.0;
+    let health = 1 + (run_stats.survival_time / HEALTH_STEP_SECONDS) as u32;
+    for index in 0..count {
+        spawn_enemy(
+            &mut commands,
+            left + FORMATION_SPACING * index as f32,
+            health,
+        );
+    }
+}
+
+// 時間切れで終わり。ランキングに入っていれば名前の入力へ
+fn finish_score_attack(
+    game_mode: Res<GameMode>,
+    run_stats: Res<RunStats>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(time_limit) = game_mode.time_limit() else {
+        return;
+    };
+    if run_stats.survival_time >= time_limit {
+        next_state.set(GameState::NameEntry);
+    }
+}
+
+impl Plugin for ScoreAttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpawnScript>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                reset_spawn_script.run_if(score_attack_mode),
+            )
+            .add_systems(
+                Update,
+                (reset_spawn_script.in_set(RestartSet), finish_score_attack)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(score_attack_mode),
+            )
+            .add_systems(
+                FixedUpdate,
+                spawn_formations
+                    .in_set(GameplaySet)
+                    .run_if(score_attack_mode),
+            );
+    }
+}
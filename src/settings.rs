@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+
+use crate::storage;
+
+const SETTINGS_FILE: &str = "settings.cfg";
+
+// 音量は 0 から この値までの段階で持つ
+pub const MAX_VOLUME: u32 = 10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "Japanese",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.name() == name)
+    }
+}
+
+// オプション画面で変更できる設定。変更したらすぐ保存する
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub fullscreen: bool,
+    pub master_volume: u32,
+    pub bgm_volume: u32,
+    pub sfx_volume: u32,
+    pub screen_shake: bool,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            master_volume: MAX_VOLUME,
+            bgm_volume: 8,
+            sfx_volume: 8,
+            screen_shake: true,
+            language: Language::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    // 全体音量を掛けた 0.0 ~ 1.0 の音量
+    pub fn bgm_gain(&self) -> f32 {
+        self.gain(self.bgm_volume)
+    }
+
+    pub fn sfx_gain(&self) -> f32 {
+        self.gain(self.sfx_volume)
+    }
+
+    fn gain(&self, volume: u32) -> f32 {
+        (self.master_volume * volume) as f32 / (MAX_VOLUME * MAX_VOLUME) as f32
+    }
+
+    // ウィンドウの生成前に読むので、App の外から呼ぶ
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        for (name, value) in storage::load_entries(SETTINGS_FILE).unwrap_or_default() {
+            let volume = value.parse().ok().map(|volume: u32| volume.min(MAX_VOLUME));
+            match name.as_str() {
+                "fullscreen" => settings.fullscreen = value == "true",
+                "master_volume" => {
+                    settings.master_volume = volume.unwrap_or(settings.master_volume);
+                }
+                "bgm_volume" => settings.bgm_volume = volume.unwrap_or(settings.bgm_volume),
+                "sfx_volume" => settings.sfx_volume = volume.unwrap_or(settings.sfx_volume),
+                "screen_shake" => settings.screen_shake = value != "false",
+                "language" => {
+                    settings.language = Language::from_name(&value).unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self) {
+        let entries = [
+            ("fullscreen", self.fullscreen.to_string()),
+            ("master_volume", self.master_volume.to_string()),
+            ("bgm_volume", self.bgm_volume.to_string()),
+            ("sfx_volume", self.sfx_volume.to_string()),
+            ("screen_shake", self.screen_shake.to_string()),
+            ("language", self.language.name().to_string()),
+        ]
+        .map(|(name, value)| (name.to_string(), value));
+        if let Err(err) = storage::save_entries(SETTINGS_FILE, &entries) {
+            warn!("Failed to save settings: {}", err);
+        }
+    }
+}
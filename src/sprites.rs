@@ -0,0 +1,255 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{
+    action::Actions,
+    game::PauseState,
+    game_feel::not_hit_stopped,
+    game_playing::{EnemyDestroyed, Player},
+};
+
+pub struct SpriteSheetPlugin;
+
+// アニメーションの 1 秒あたりのコマ数
+const ANIMATION_FPS: f32 = 12.0;
+
+// 傾きアニメーションに切り替える横入力の強さ
+const BANK_THRESHOLD: f32 = 0.2;
+
+// 画像ファイルごとのコマの並び
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Sheet {
+    Player,
+    Enemy,
+    Bullets,
+    Explosion,
+}
+
+impl Sheet {
+    const ALL: [Sheet; 4] = [
+        Sheet::Player,
+        Sheet::Enemy,
+        Sheet::Bullets,
+        Sheet::Explosion,
+    ];
+
+    fn path(&self) -> &'static str {
+        match self {
+            Sheet::Player => "sprites/player.png",
+            Sheet::Enemy => "sprites/enemy.png",
+            Sheet::Bullets => "sprites/bullets.png",
+            Sheet::Explosion => "sprites/explosion.png",
+        }
+    }
+
+    // 1 コマの大きさと、横と縦のコマ数
+    fn grid(&self) -> (Vec2, usize, usize) {
+        match self {
+            Sheet::Player => (Vec2::splat(32.0), 4, 3),
+            Sheet::Enemy => (Vec2::splat(32.0), 4, 1),
+            Sheet::Bullets => (Vec2::splat(8.0), 4, 2),
+            Sheet::Explosion => (Vec2::splat(32.0), 8, 1),
+        }
+    }
+}
+
+// 画像の差し替え対象。画像が読めるまでは元の色付きの四角のまま表示する
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpriteKind {
+    Player,
+    Enemy,
+    PlayerShot,
+    EnemyShot,
+    Explosion,
+}
+
+impl SpriteKind {
+    fn sheet(&self) -> Sheet {
+        match self {
+            SpriteKind::Player => Sheet::Player,
+            SpriteKind::Enemy => Sheet::Enemy,
+            SpriteKind::PlayerShot | SpriteKind::EnemyShot => Sheet::Bullets,
+            SpriteKind::Explosion => Sheet::Explosion,
+        }
+    }
+
+    // 動きの最初のコマ番号とコマ数。無い動きは待機のコマを使う
+    fn frames(&self, clip: AnimationClip) -> (usize, usize) {
+        match (self, clip) {
+            (SpriteKind::Player, AnimationClip::BankLeft) => (4, 4),
+            (SpriteKind::Player, AnimationClip::BankRight) => (8, 4),
+            (SpriteKind::Player, _) => (0, 4),
+            (SpriteKind::Enemy, _) => (0, 4),
+            (SpriteKind::PlayerShot, _) => (0, 4),
+            (SpriteKind::EnemyShot, _) => (4, 4),
+            (SpriteKind::Explosion, _) => (0, 8),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum AnimationClip {
+    #[default]
+    Idle,
+    BankLeft,
+    BankRight,
+    // 最後のコマまで再生したら消える
+    Explode,
+}
+
+#[derive(Component, Debug, Default)]
+struct SpriteAnimation {
+    clip: AnimationClip,
+    frame: usize,
+    elapsed: f32,
+}
+
+impl SpriteAnimation {
+    fn play(&mut self, clip: AnimationClip) {
+        if self.clip != clip {
+            *self = SpriteAnimation { clip, ..default() };
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct SpriteSheets(HashMap<Sheet, (Handle<Image>, Handle<TextureAtlas>)>);
+
+impl SpriteSheets {
+    // 読み込みが終わった画像のアトラスだけを返す
+    fn loaded(&self, sheet: Sheet, asset_server: &AssetServer) -> Option<Handle<TextureAtlas>> {
+        let (image, atlas) = self.0.get(&sheet)?;
+        (asset_server.get_load_state(image) == LoadState::Loaded).then(|| atlas.clone())
+    }
+}
+
+fn load_sprite_sheets(
+    asset_server: Res<AssetServer>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    mut commands: Commands,
+) {
+    let mut sheets = HashMap::new();
+    for sheet in Sheet::ALL {
+        let image = asset_server.load(sheet.path());
+        let (tile_size, columns, rows) = sheet.grid();
+        let atlas = TextureAtlas::from_grid(image.clone(), tile_size, columns, rows, None, None);
+        sheets.insert(sheet, (image, atlases.add(atlas)));
+    }
+    commands.insert_resource(SpriteSheets(sheets));
+}
+
+// 画像が読めたものから、同じ大きさと色のままアトラスの表示に差し替える
+fn apply_sprite_sheets(
+    sheets: Res<SpriteSheets>,
+    asset_server: Res<AssetServer>,
+    sprite_query: Query<(Entity, &SpriteKind, &Sprite)>,
+    mut commands: Commands,
+) {
+    for (entity, kind, sprite) in sprite_query.iter() {
+        let Some(atlas) = sheets.loaded(kind.sheet(), &asset_server) else {
+            continue;
+        };
+        commands
+            .entity(entity)
+            .remove::<(Sprite, Handle<Image>)>()
+            .insert((
+                TextureAtlasSprite {
+                    index: kind.frames(AnimationClip::Idle).0,
+                    color: sprite.color,
+                    custom_size: sprite.rect.map(|rect| rect.size()).or(sprite.custom_size),
+                    ..default()
+                },
+                atlas,
+                SpriteAnimation::default(),
+            ));
+    }
+}
+
+fn spawn_explosion_sprites(
+    mut destroyed: EventReader<EnemyDestroyed>,
+    sheets: Res<SpriteSheets>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    // 画像が無いときはパーティクルだけで表現する
+    let Some(atlas) = sheets.loaded(Sheet::Explosion, &asset_server) else {
+        destroyed.clear();
+        return;
+    };
+    for event in destroyed.iter() {
+        commands.spawn((
+            SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    custom_size: Some(Vec2::splat(64.0)),
+                    ..default()
+                },
+                texture_atlas: atlas.clone(),
+                transform: Transform::from_translation(event.0 + Vec3::Z * 0.5),
+                ..default()
+            },
+            SpriteKind::Explosion,
+            SpriteAnimation {
+                clip: AnimationClip::Explode,
+                ..default()
+            },
+        ));
+    }
+}
+
+fn bank_players(actions: Actions, mut player_query: Query<(&Player, &mut SpriteAnimation)>) {
+    for (player, mut animation) in player_query.iter_mut() {
+        let x = actions.direction(player.0).x;
+        animation.play(if x < -BANK_THRESHOLD {
+            AnimationClip::BankLeft
+        } else if x > BANK_THRESHOLD {
+            AnimationClip::BankRight
+        } else {
+            AnimationClip::Idle
+        });
+    }
+}
+
+fn animate_sprites(
+    time_step: Res<FixedTime>,
+    mut sprite_query: Query<(
+        Entity,
+        &SpriteKind,
+        &mut SpriteAnimation,
+        &mut TextureAtlasSprite,
+    )>,
+    mut commands: Commands,
+) {
+    let frame_time = 1.0 / ANIMATION_FPS;
+    for (entity, kind, mut animation, mut sprite) in sprite_query.iter_mut() {
+        let (first, count) = kind.frames(animation.clip);
+        animation.elapsed += time_step.period.as_secs_f32();
+        while animation.elapsed >= frame_time {
+            animation.elapsed -= frame_time;
+            animation.frame += 1;
+        }
+        if animation.frame >= count {
+            if animation.clip == AnimationClip::Explode {
+                commands.entity(entity).despawn();
+                continue;
+            }
+            animation.frame %= count;
+        }
+        sprite.index = first + animation.frame;
+    }
+}
+
+impl Plugin for SpriteSheetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_sprite_sheets)
+            .add_systems(Update, (apply_sprite_sheets, spawn_explosion_sprites))
+            // 爆発はプレイ以外の画面でも最後まで動かすので GameplaySet には入れない
+            .add_systems(
+                FixedUpdate,
+                (bank_players, animate_sprites)
+                    .chain()
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(not_hit_stopped),
+            );
+    }
+}
@@ -0,0 +1,463 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    action::{Action, Actions, PROMPT_INPUT_DELAY},
+    boss::{spawn_boss, BossData, BossDefeated, BossPattern, BossPhase},
+    camera::{HUD_WIDTH, PLAYFIELD_WIDTH},
+    game::{GameMode, GameState, PauseState, Practice, StagePhase},
+    game_playing::{
+        spawn_enemy, Bombs, Enemy, GameplaySet, Lives, PlayerHit, RestartSet, Score, Stage,
+    },
+    locale::Locale,
+    rng::GameRng,
+    starfield::ScrollSpeed,
+    state_scoped::StateScoped,
+};
+
+pub struct StagePlugin;
+
+// ステージ名を出している秒数
+const INTRO_SECONDS: f32 = 2.5;
+
+// 集計画面を出している秒数。決定で飛ばせる
+const CLEAR_SECONDS: f32 = 5.0;
+
+// 敵がいなくなってから次のウェーブまでの間
+const WAVE_BREAK_SECONDS: f32 = 1.5;
+
+// ステージクリア時のボーナス
+const CLEAR_BONUS: usize = 1000;
+const LIFE_BONUS: usize = 500;
+const BOMB_BONUS: usize = 200;
+const NO_MISS_BONUS: usize = 2000;
+
+// 一定間隔で count 体の敵を出す
+struct Wave {
+    count: usize,
+    interval: f32,
+}
+
+pub struct StageData {
+    waves: &'static [Wave],
+    pub boss: BossData,
+    scroll_speed: f32,
+}
+
+pub static STAGES: [StageData; 3] = [
+    StageData {
+        waves: &[
+            Wave {
+                count: 5,
+                interval: 0.8,
+            },
+            Wave {
+                count: 8,
+                interval: 0.6,
+            },
+            Wave {
+                count: 10,
+                interval: 0.5,
+            },
+        ],
+        boss: BossData {
+            phases: &[
+                BossPhase {
+                    health: 40,
+                    time_limit: 20.0,
+                    pattern: BossPattern::Aimed,
+                    interval: 0.8,
+                },
+                BossPhase {
+                    health: 50,
+                    time_limit: 25.0,
+                    pattern: BossPattern::Ring,
+                    interval: 1.2,
+                },
+            ],
+        },
+        scroll_speed: 120.0,
+    },
+    StageData {
+        waves: &[
+            Wave {
+                count: 8,
+                interval: 0.6,
+            },
+            Wave {
+                count: 12,
+                interval: 0.45,
+            },
+            Wave {
+                count: 15,
+                interval: 0.4,
+            },
+        ],
+        boss: BossData {
+            phases: &[
+                BossPhase {
+                    health: 60,
+                    time_limit: 25.0,
+                    pattern: BossPattern::Ring,
+                    interval: 1.0,
+                },
+                BossPhase {
+                    health: 60,
+                    time_limit: 25.0,
+                    pattern: BossPattern::Spiral,
+                    interval: 0.15,
+                },
+                BossPhase {
+                    health: 70,
+                    time_limit: 30.0,
+                    pattern: BossPattern::Aimed,
+                    interval: 0.5,
+                },
+            ],
+        },
+        scroll_speed: 180.0,
+    },
+    StageData {
+        waves: &[
+            Wave {
+                count: 10,
+                interval: 0.5,
+            },
+            Wave {
+                count: 15,
+                interval: 0.35,
+            },
+            Wave {
+                count: 20,
+                interval: 0.3,
+            },
+        ],
+        boss: BossData {
+            phases: &[
+                BossPhase {
+                    health: 80,
+                    time_limit: 30.0,
+                    pattern: BossPattern::Spiral,
+                    interval: 0.1,
+                },
+                BossPhase {
+                    health: 80,
+                    time_limit: 30.0,
+                    pattern: BossPattern::Ring,
+                    interval: 0.7,
+                },
+                BossPhase {
+                    health: 100,
+                    time_limit: 40.0,
+                    pattern: BossPattern::Aimed,
+                    interval: 0.3,
+                },
+            ],
+        },
+        scroll_speed: 260.0,
+    },
+];
+
+// 今のステージの進み具合
+#[derive(Resource, Debug, Default)]
+struct StageProgress {
+    elapsed: f32,
+    wave: usize,
+    spawned: usize,
+    // このステージでやられたか
+    missed: bool,
+}
+
+fn current_stage(stage: &Stage) -> &'static StageData {
+    &STAGES[(stage.0 - 1).min(STAGES.len() - 1)]
+}
+
+// ボスの 1 段階だけを練習しているときはその段階
+fn practice_boss_phase(game_mode: GameMode, practice: &Practice) -> Option<usize> {
+    match game_mode {
+        GameMode::Practice => practice.boss_phase,
+        _ => None,
+    }
+}
+
+// プレイ領域の上に重ねる UI の土台。HUD の分を除いた左側の中央に置く
+pub fn playfield_overlay() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(HUD_WIDTH),
+            top: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+// ステージを進めるモードのときだけ動かす
+fn stage_mode(game_mode: Res<GameMode>) -> bool {
+    game_mode.has_stages()
+}
+
+fn start_stage(mut next_stage_phase: ResMut<NextState<StagePhase>>) {
+    next_stage_phase.set(StagePhase::Intro);
+}
+
+// やり直すときは一度 Inactive に戻して、最初のステージから始め直す
+fn restart_stage(mut next_stage_phase: ResMut<NextState<StagePhase>>) {
+    next_stage_phase.set(StagePhase::Inactive);
+}
+
+// ボスラッシュとボスの練習ではウェーブを飛ばしてすぐボスを出す
+fn stage_intro(
+    stage: Res<Stage>,
+    game_mode: Res<GameMode>,
+    practice: Res<Practice>,
+    locale: Res<Locale>,
+    mut scroll_speed: ResMut<ScrollSpeed>,
+    mut commands: Commands,
+) {
+    let stage_data = current_stage(&stage);
+    let skip_waves =
+        *game_mode == GameMode::BossRush || practice_boss_phase(*game_mode, &practice).is_some();
+    commands.insert_resource(StageProgress {
+        wave: if skip_waves {
+            stage_data.waves.len()
+        } else {
+            0
+        },
+        ..default()
+    });
+    scroll_speed.0 = stage_data.scroll_speed;
+    let key = match *game_mode {
+        GameMode::BossRush => "stage.boss_rush_intro",
+        _ => "stage.intro",
+    };
+    commands
+        .spawn((playfield_overlay(), StateScoped(StagePhase::Intro)))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.format(key, &[&stage.0]),
+                TextStyle {
+                    font_size: 80.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn tick_intro(
+    time_step: Res<FixedTime>,
+    mut progress: ResMut<StageProgress>,
+    mut next_stage_phase: ResMut<NextState<StagePhase>>,
+) {
+    progress.elapsed += time_step.period.as_secs_f32();
+    if progress.elapsed >= INTRO_SECONDS {
+        progress.elapsed = 0.0;
+        next_stage_phase.set(StagePhase::Waves);
+    }
+}
+
+// ウェーブの敵を出し切って全滅させたら次のウェーブ、最後ならボスへ
+fn spawn_waves(
+    time_step: Res<FixedTime>,
+    stage: Res<Stage>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut progress: ResMut<StageProgress>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_stage_phase: ResMut<NextState<StagePhase>>,
+    mut commands: Commands,
+) {
+    let waves = current_stage(&stage).waves;
+    let Some(wave) = waves.get(progress.wave) else {
+        next_stage_phase.set(StagePhase::Boss);
+        return;
+    };
+    progress.elapsed += time_step.period.as_secs_f32();
+    if progress.spawned < wave.count {
+        if progress.elapsed >= wave.interval {
+            progress.elapsed = 0.0;
+            progress.spawned += 1;
+            let half_width = PLAYFIELD_WIDTH / 2.0 - 50.0;
+            let x = game_rng.layout_rng().gen_range(-half_width, half_width);
+            spawn_enemy(&mut commands, x, 1);
+        }
+        return;
+    }
+    if !enemy_query.is_empty() {
+        progress.elapsed = 0.0;
+        return;
+    }
+    if progress.elapsed >= WAVE_BREAK_SECONDS {
+        progress.elapsed = 0.0;
+        progress.spawned = 0;
+        progress.wave += 1;
+    }
+}
+
+fn stage_boss(
+    stage: Res<Stage>,
+    game_mode: Res<GameMode>,
+    practice: Res<Practice>,
+    mut commands: Commands,
+) {
+    let boss = &current_stage(&stage).boss;
+    let phases = match practice_boss_phase(*game_mode, &practice) {
+        Some(phase) => phase..phase + 1,
+        None => 0..boss.phases.len(),
+    };
+    spawn_boss(&mut commands, boss, phases);
+}
+
+// ボスラッシュは集計を挟まずに次のボスへ進む
+fn finish_boss(
+    mut defeated: EventReader<BossDefeated>,
+    game_mode: Res<GameMode>,
+    mut stage: ResMut<Stage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_stage_phase: ResMut<NextState<StagePhase>>,
+) {
+    if defeated.iter().count() == 0 {
+        return;
+    }
+    if *game_mode != GameMode::BossRush {
+        next_stage_phase.set(StagePhase::Clear);
+    } else if stage.0 < STAGES.len() {
+        stage.0 += 1;
+        next_stage_phase.set(StagePhase::Intro);
+    } else {
+        next_state.set(GameState::NameEntry);
+    }
+}
+
+fn track_misses(mut player_hit: EventReader<PlayerHit>, progress: Option<ResMut<StageProgress>>) {
+    if player_hit.iter().count() == 0 {
+        return;
+    }
+    if let Some(mut progress) = progress {
+        progress.missed = true;
+    }
+}
+
+// 残機とボムとノーミスのボーナスを足して表示する
+fn stage_clear(
+    stage: Res<Stage>,
+    lives: Res<Lives>,
+    bombs: Res<Bombs>,
+    locale: Res<Locale>,
+    mut progress: ResMut<StageProgress>,
+    mut score: ResMut<Score>,
+    mut commands: Commands,
+) {
+    let no_miss_bonus = if progress.missed { 0 } else { NO_MISS_BONUS };
+    let bonuses = [
+        ("stage.clear_bonus", CLEAR_BONUS * stage.0),
+        ("stage.lives_bonus", LIFE_BONUS * lives.0),
+        ("stage.bombs_bonus", BOMB_BONUS * bombs.0),
+        ("stage.no_miss_bonus", no_miss_bonus),
+    ];
+    let total: usize = bonuses.iter().map(|(_, bonus)| bonus).sum();
+    score.0 += total;
+    progress.elapsed = 0.0;
+    commands
+        .spawn((playfield_overlay(), StateScoped(StagePhase::Clear)))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.format("stage.clear", &[&stage.0]),
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            for (key, bonus) in bonuses {
+                parent.spawn(TextBundle::from_section(
+                    locale.format(key, &[&bonus]),
+                    TextStyle {
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            }
+            parent.spawn(TextBundle::from_section(
+                locale.format("stage.total", &[&total]),
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::YELLOW,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn tick_clear(time_step: Res<FixedTime>, mut progress: ResMut<StageProgress>) {
+    progress.elapsed += time_step.period.as_secs_f32();
+}
+
+// 最後のステージならエンディングへ。練習は 1 ステージで終わる
+fn finish_clear(
+    actions: Actions,
+    progress: Res<StageProgress>,
+    game_mode: Res<GameMode>,
+    mut stage: ResMut<Stage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_stage_phase: ResMut<NextState<StagePhase>>,
+) {
+    let skipped =
+        progress.elapsed >= PROMPT_INPUT_DELAY && actions.any_just_pressed(Action::Confirm);
+    if progress.elapsed < CLEAR_SECONDS && !skipped {
+        return;
+    }
+    if *game_mode == GameMode::Practice {
+        next_state.set(GameState::Result);
+    } else if stage.0 < STAGES.len() {
+        stage.0 += 1;
+        next_stage_phase.set(StagePhase::Intro);
+    } else {
+        next_state.set(GameState::Ending);
+    }
+}
+
+fn reset_stage_phase(mut next_stage_phase: ResMut<NextState<StagePhase>>) {
+    next_stage_phase.set(StagePhase::Inactive);
+}
+
+impl Plugin for StagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(StagePhase::Intro), stage_intro)
+            .add_systems(OnEnter(StagePhase::Boss), stage_boss)
+            .add_systems(OnEnter(StagePhase::Clear), stage_clear)
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_intro.run_if(in_state(StagePhase::Intro)),
+                    spawn_waves.run_if(in_state(StagePhase::Waves)),
+                    tick_clear.run_if(in_state(StagePhase::Clear)),
+                )
+                    .in_set(GameplaySet),
+            )
+            .add_systems(
+                Update,
+                (
+                    start_stage
+                        .run_if(in_state(StagePhase::Inactive))
+                        .run_if(stage_mode),
+                    restart_stage.in_set(RestartSet),
+                    finish_boss.run_if(in_state(StagePhase::Boss)),
+                    track_misses,
+                    finish_clear
+                        .run_if(in_state(StagePhase::Clear))
+                        .run_if(in_state(PauseState::Running)),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), reset_stage_phase);
+    }
+}
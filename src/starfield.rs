@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    camera::{PLAYFIELD_HEIGHT, PLAYFIELD_WIDTH},
+    game::GameState,
+    game_playing::{GameplaySet, RestartSet},
+    rng::{reseed_game_rng, GameRng},
+    state_scoped::StateScoped,
+};
+
+pub struct StarfieldPlugin;
+
+// 背景の一番奥に敷く色
+const BACKDROP_COLOR: Color = Color::rgb(0.02, 0.02, 0.08);
+
+// 画面外に出てから反対側に戻すまでの余白
+const WRAP_MARGIN: f32 = 200.0;
+
+// 背景が流れる速さ (ピクセル/秒)。各層はこれに倍率を掛けた速さで流れる
+#[derive(Resource, Debug)]
+pub struct ScrollSpeed(pub f32);
+
+impl Default for ScrollSpeed {
+    fn default() -> Self {
+        Self(120.0)
+    }
+}
+
+// 背景の 1 層分の設定。奥の層ほどゆっくり流れる
+struct Layer {
+    count: usize,
+    speed_ratio: f32,
+    size: (f32, f32),
+    alpha: (f32, f32),
+    color: Color,
+    z: f32,
+}
+
+// 自機やその噴射 (z = -0.05) より奥に描く
+const LAYERS: [Layer; 4] = [
+    // 星雲
+    Layer {
+        count: 8,
+        speed_ratio: 0.1,
+        size: (200.0, 450.0),
+        alpha: (0.04, 0.1),
+        color: Color::rgb(0.5, 0.2, 0.8),
+        z: -0.09,
+    },
+    Layer {
+        count: 120,
+        speed_ratio: 0.25,
+        size: (1.0, 2.0),
+        alpha: (0.3, 0.6),
+        color: Color::WHITE,
+        z: -0.08,
+    },
+    Layer {
+        count: 60,
+        speed_ratio: 0.5,
+        size: (2.0, 3.0),
+        alpha: (0.5, 0.8),
+        color: Color::WHITE,
+        z: -0.07,
+    },
+    Layer {
+        count: 25,
+        speed_ratio: 1.0,
+        size: (3.0, 4.0),
+        alpha: (0.8, 1.0),
+        color: Color::rgb(0.8, 0.9, 1.0),
+        z: -0.06,
+    },
+];
+
+#[derive(Component, Debug)]
+struct Star {
+    speed_ratio: f32,
+}
+
+// 配置はプレイの乱数から取ったシードで決めるので、同じシードなら同じ背景になる
+fn spawn_starfield(mut game_rng: ResMut<GameRng>, mut commands: Commands) {
+    let mut rng = StdRng::seed_from_u64(game_rng.rng().gen());
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: BACKDROP_COLOR,
+                custom_size: Some(Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, -0.095),
+            ..default()
+        },
+        StateScoped(GameState::Playing),
+    ));
+    for layer in LAYERS.iter() {
+        for _ in 0..layer.count {
+            let size = rng.gen_range(layer.size.0, layer.size.1);
+            let x = rng.gen_range(-PLAYFIELD_WIDTH / 2.0, PLAYFIELD_WIDTH / 2.0);
+            let y = rng.gen_range(
+                -PLAYFIELD_HEIGHT / 2.0 - WRAP_MARGIN,
+                PLAYFIELD_HEIGHT / 2.0 + WRAP_MARGIN,
+            );
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: layer
+                            .color
+                            .with_a(rng.gen_range(layer.alpha.0, layer.alpha.1)),
+                        custom_size: Some(Vec2::splat(size)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(x, y, layer.z),
+                    ..default()
+                },
+                Star {
+                    speed_ratio: layer.speed_ratio,
+                },
+                StateScoped(GameState::Playing),
+            ));
+        }
+    }
+}
+
+fn scroll_starfield(
+    time_step: Res<FixedTime>,
+    scroll_speed: Res<ScrollSpeed>,
+    mut star_query: Query<(&Star, &mut Transform)>,
+) {
+    let delta = time_step.period.as_secs_f32();
+    let bottom = -PLAYFIELD_HEIGHT / 2.0 - WRAP_MARGIN;
+    for (star, mut transform) in star_query.iter_mut() {
+        transform.translation.y -= scroll_speed.0 * star.speed_ratio * delta;
+        if transform.translation.y < bottom {
+            transform.translation.y += PLAYFIELD_HEIGHT + WRAP_MARGIN * 2.0;
+        }
+    }
+}
+
+impl Plugin for StarfieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScrollSpeed>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_starfield.after(reseed_game_rng),
+            )
+            .add_systems(
+                Update,
+                spawn_starfield.after(reseed_game_rng).in_set(RestartSet),
+            )
+            .add_systems(FixedUpdate, scroll_starfield.in_set(GameplaySet));
+    }
+}
@@ -0,0 +1,36 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+// 付けたエンティティは、指定した状態を抜けるときに子ごと消える
+#[derive(Component, Debug)]
+pub struct StateScoped<S: States>(pub S);
+
+// S の全ての状態について、抜けるときの後片付けを登録する
+pub struct StateScopedPlugin<S: States>(PhantomData<S>);
+
+impl<S: States> Default for StateScopedPlugin<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+fn despawn_state_scoped<S: States>(
+    exited: S,
+) -> impl FnMut(Query<(Entity, &StateScoped<S>)>, Commands) {
+    move |scoped_query: Query<(Entity, &StateScoped<S>)>, mut commands: Commands| {
+        for (entity, scoped) in scoped_query.iter() {
+            if scoped.0 == exited {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+impl<S: States> Plugin for StateScopedPlugin<S> {
+    fn build(&self, app: &mut App) {
+        for state in S::variants() {
+            app.add_systems(OnExit(state.clone()), despawn_state_scoped(state));
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+// 設定ファイルやセーブデータを置くディレクトリ
+pub fn data_dir() -> PathBuf {
+    let base = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("my_bevy_game")
+}
+
+// `key = value` 形式の行を読む。空行と # から始まる行は飛ばす
+pub fn parse_entries(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+// データディレクトリのファイルを読む。無ければ None
+pub fn load_entries(file_name: &str) -> Option<Vec<(String, String)>> {
+    let text = fs::read_to_string(data_dir().join(file_name)).ok()?;
+    Some(parse_entries(&text))
+}
+
+pub fn save_entries(file_name: &str, entries: &[(String, String)]) -> io::Result<()> {
+    let dir = data_dir();
+    fs::create_dir_all(&dir)?;
+    let text: String = entries
+        .iter()
+        .map(|(key, value)| format!("{} = {}\n", key, value))
+        .collect();
+    fs::write(dir.join(file_name), text)
+}
@@ -0,0 +1,159 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    camera::PLAYFIELD_WIDTH,
+    game::{GameMode, GameState},
+    game_playing::{aim_at_player, spawn_enemy, spawn_enemy_shot, GameplaySet, Player, RestartSet},
+    rng::GameRng,
+    run_stats::RunStats,
+    starfield::ScrollSpeed,
+};
+
+pub struct SurvivalPlugin;
+
+// 難易度が 1 上がるまでの秒数の目安。後半ほど上がり方が速くなる
+const LEVEL_SECONDS: f32 = 45.0;
+const LEVEL_CURVE: f32 = 1.3;
+
+// 出現間隔 (秒) は難易度に反比例して縮み、この値より短くはならない
+const BASE_SPAWN_INTERVAL: f32 = 1.5;
+const MIN_SPAWN_INTERVAL: f32 = 0.2;
+
+const BASE_FIRE_INTERVAL: f32 = 2.5;
+const MIN_FIRE_INTERVAL: f32 = 0.5;
+
+const BASE_SCROLL_SPEED: f32 = 120.0;
+const MAX_SCROLL_SPEED: f32 = 400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GunPattern {
+    // 真下に 1 発
+    Straight,
+    // 自機を狙って 1 発
+    Aimed,
+    // 自機を狙う 5 方向弾
+    Spread,
+    // 全方向に一斉に撃つ
+    Ring,
+}
+
+// Survival の敵が持つ砲台
+#[derive(Component, Debug)]
+struct EnemyGun {
+    pattern: GunPattern,
+    interval: f32,
+    elapsed: f32,
+}
+
+#[derive(Resource, Debug, Default)]
+struct SurvivalSpawner {
+    elapsed: f32,
+}
+
+// 経過秒数から難易度を出す。0 から始まって時間とともに上がり続ける
+fn difficulty(seconds: f32) -> f32 {
+    (seconds / LEVEL_SECONDS).powf(LEVEL_CURVE)
+}
+
+fn gun_pattern(level: f32) -> GunPattern {
+    match level as u32 {
+        0 => GunPattern::Straight,
+        1 => GunPattern::Aimed,
+        2 => GunPattern::Spread,
+        _ => GunPattern::Ring,
+    }
+}
+
+fn survival_mode(game_mode: Res<GameMode>) -> bool {
+    *game_mode == GameMode::Survival
+}
+
+fn reset_spawner(mut commands: Commands) {
+    commands.insert_resource(SurvivalSpawner::default());
+}
+
+fn spawn_survival_enemies(
+    time_step: Res<FixedTime>,
+    run_stats: Res<RunStats>,
+    mut spawner: ResMut<SurvivalSpawner>,
+    mut game_rng: ResMut<GameRng>,
+    mut commands: Commands,
+) {
+    let level = difficulty(run_stats.survival_time);
+    let interval = (BASE_SPAWN_INTERVAL / (1.0 + level)).max(MIN_SPAWN_INTERVAL);
+    spawner.elapsed += time_step.period.as_secs_f32();
+    if spawner.elapsed < interval {
+        return;
+    }
+    spawner.elapsed -= interval;
+    let half_width = PLAYFIELD_WIDTH / 2.0 - 50.0;
+    let x = game_rng.layout_rng().gen_range(-half_width, half_width);
+    let health = 1 + (level * 2.0) as u32;
+    let enemy = spawn_enemy(&mut commands, x, health);
+    commands.entity(enemy).insert(EnemyGun {
+        pattern: gun_pattern(level),
+        interval: (BASE_FIRE_INTERVAL / (1.0 + level * 0.5)).max(MIN_FIRE_INTERVAL),
+        elapsed: 0.0,
+    });
+}
+
+fn fire_enemy_guns(
+    time_step: Res<FixedTime>,
+    mut gun_query: Query<(&mut EnemyGun, &Transform)>,
+    player_query: Query<&Transform, With<Player>>,
+    mut commands: Commands,
+) {
+    let delta = time_step.period.as_secs_f32();
+    for (mut gun, transform) in gun_query.iter_mut() {
+        gun.elapsed += delta;
+        if gun.elapsed < gun.interval {
+            continue;
+        }
+        gun.elapsed -= gun.interval;
+        let origin = transform.translation;
+        let directions: Vec<Vec2> = match gun.pattern {
+            GunPattern::Straight => vec![Vec2::NEG_Y],
+            GunPattern::Aimed => vec![aim_at_player(&player_query, origin)],
+            GunPattern::Spread => {
+                let aim = aim_at_player(&player_query, origin);
+                [-0.4_f32, -0.2, 0.0, 0.2, 0.4]
+                    .into_iter()
+                    .map(|angle| Vec2::from_angle(angle).rotate(aim))
+                    .collect()
+            }
+            GunPattern::Ring => (0..12)
+                .map(|index| Vec2::from_angle(TAU * index as f32 / 12.0))
+                .collect(),
+        };
+        for direction in directions {
+            spawn_enemy_shot(&mut commands, origin, direction * 300.0);
+        }
+    }
+}
+
+// 難易度に合わせて背景の流れも速くする
+fn escalate_scroll_speed(run_stats: Res<RunStats>, mut scroll_speed: ResMut<ScrollSpeed>) {
+    let level = difficulty(run_stats.survival_time);
+    scroll_speed.0 = (BASE_SCROLL_SPEED * (1.0 + level * 0.5)).min(MAX_SCROLL_SPEED);
+}
+
+impl Plugin for SurvivalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SurvivalSpawner>()
+            .add_systems(OnEnter(GameState::Playing), reset_spawner)
+            .add_systems(Update, reset_spawner.in_set(RestartSet))
+            .add_systems(
+                FixedUpdate,
+                (
+                    spawn_survival_enemies,
+                    fire_enemy_guns,
+                    escalate_scroll_speed,
+                )
+                    .in_set(GameplaySet)
+                    .run_if(survival_mode),
+            );
+    }
+}
@@ -0,0 +1,418 @@
+use std::{fs, sync::Arc, time::Duration};
+
+use bevy::asset::FileAssetIo;
+use bevy::audio::{Decodable, Source};
+use bevy::prelude::*;
+use bevy::reflect::{TypePath, TypeUuid};
+use bevy::utils::HashMap;
+
+use crate::storage;
+
+const SAMPLE_RATE: u32 = 44100;
+
+const PRESETS_FILE: &str = "sfx_presets.cfg";
+const SONG_PRESETS_FILE: &str = "bgm_presets.cfg";
+
+// assets に置いたファイルと同じ内容。ファイルが無くても音が出るように組み込んでおく
+const DEFAULT_PRESETS: &str = include_str!("../assets/sfx_presets.cfg");
+const DEFAULT_SONG_PRESETS: &str = include_str!("../assets/bgm_presets.cfg");
+
+// 鳴り始めのプチノイズを消すための立ち上がり時間
+const ATTACK_SECONDS: f32 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Waveform {
+    Square,
+    Saw,
+    Triangle,
+    Sine,
+    Noise,
+}
+
+impl Waveform {
+    fn from_name(name: &str) -> Option<Waveform> {
+        match name {
+            "square" => Some(Waveform::Square),
+            "saw" => Some(Waveform::Saw),
+            "triangle" => Some(Waveform::Triangle),
+            "sine" => Some(Waveform::Sine),
+            "noise" => Some(Waveform::Noise),
+            _ => None,
+        }
+    }
+}
+
+// 波形を 1 サンプルずつ作る。位相とノイズの値は音符をまたいで持ち越す
+struct Oscillator {
+    phase: f32,
+    noise: f32,
+    seed: u32,
+}
+
+impl Oscillator {
+    fn new() -> Self {
+        let mut oscillator = Self {
+            phase: 0.0,
+            noise: 0.0,
+            seed: 0x2545_f491,
+        };
+        oscillator.next_noise();
+        oscillator
+    }
+
+    fn next_noise(&mut self) {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.noise = self.seed as f32 / u32::MAX as f32 * 2.0 - 1.0;
+    }
+
+    fn sample(&mut self, waveform: Waveform, frequency: f32) -> f32 {
+        let phase = self.phase;
+        let next_phase = phase + frequency / SAMPLE_RATE as f32;
+        let wave = match waveform {
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Saw => phase * 2.0 - 1.0,
+            Waveform::Triangle => 1.0 - (phase * 4.0 - 2.0).abs(),
+            Waveform::Sine => (phase * std::f32::consts::TAU).sin(),
+            Waveform::Noise => {
+                // 1 周期ごとに値を変えるとファミコン風のノイズになる
+                if next_phase >= 1.0 {
+                    self.next_noise();
+                }
+                self.noise
+            }
+        };
+        self.phase = next_phase.fract();
+        wave
+    }
+}
+
+// 鳴り始めだけ立ち上げ、あとは長さいっぱいで 0 まで減衰させる
+fn envelope(index: usize, progress: f32) -> f32 {
+    let time = index as f32 / SAMPLE_RATE as f32;
+    (time / ATTACK_SECONDS).min(1.0) * (1.0 - progress)
+}
+
+// 周波数を開始値から終了値まで滑らせながら鳴らす単純な音色
+#[derive(Debug, Clone, PartialEq)]
+pub struct SynthPreset {
+    waveform: Waveform,
+    start_frequency: f32,
+    end_frequency: f32,
+    duration: f32,
+    volume: f32,
+}
+
+impl SynthPreset {
+    // `波形 開始周波数 終了周波数 長さ 音量` の形を読む
+    fn parse(value: &str) -> Option<SynthPreset> {
+        let mut tokens = value.split_whitespace();
+        let waveform = Waveform::from_name(tokens.next()?)?;
+        let mut number = || tokens.next()?.parse::<f32>().ok();
+        let preset = SynthPreset {
+            waveform,
+            start_frequency: number()?,
+            end_frequency: number()?,
+            duration: number()?,
+            volume: number()?.clamp(0.0, 1.0),
+        };
+        let valid =
+            preset.start_frequency > 0.0 && preset.end_frequency > 0.0 && preset.duration > 0.0;
+        valid.then_some(preset)
+    }
+
+    fn render(&self) -> Vec<f32> {
+        let length = (self.duration * SAMPLE_RATE as f32) as usize;
+        let mut samples = Vec::with_capacity(length);
+        let mut oscillator = Oscillator::new();
+        for index in 0..length {
+            let progress = index as f32 / length as f32;
+            // 音程の変化が耳に均等に聞こえるよう指数的に動かす
+            let frequency =
+                self.start_frequency * (self.end_frequency / self.start_frequency).powf(progress);
+            let wave = oscillator.sample(self.waveform, frequency);
+            samples.push(wave * envelope(index, progress) * self.volume);
+        }
+        samples
+    }
+}
+
+// C4 や F#3 のような音名を周波数にする。A4 が 440Hz
+fn note_frequency(name: &str) -> Option<f32> {
+    let mut chars = name.chars();
+    let semitone = match chars.next()? {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (semitone, octave) = match rest.strip_prefix('#') {
+        Some(octave) => (semitone + 1, octave),
+        None => (semitone, rest),
+    };
+    let note = (octave.parse::<i32>().ok()? + 1) * 12 + semitone;
+    Some(440.0 * 2.0_f32.powf((note - 69) as f32 / 12.0))
+}
+
+// BGM の 1 パート。8 分音符を順に鳴らす
+#[derive(Debug, Clone, PartialEq)]
+pub struct SongVoice {
+    waveform: Waveform,
+    step: f32,
+    volume: f32,
+    // 音符ごとの周波数。None は休符
+    notes: Vec<Option<f32>>,
+}
+
+impl SongVoice {
+    // `波形 テンポ 音量 音符...` の形を読む
+    fn parse(value: &str) -> Option<SongVoice> {
+        let mut tokens = value.split_whitespace();
+        let waveform = Waveform::from_name(tokens.next()?)?;
+        let tempo = tokens.next()?.parse::<f32>().ok()?;
+        let volume = tokens.next()?.parse::<f32>().ok()?.clamp(0.0, 1.0);
+        let notes = tokens
+            .map(|token| match token {
+                "-" => Some(None),
+                _ => note_frequency(token).map(Some),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let valid = tempo > 0.0 && !notes.is_empty();
+        valid.then_some(SongVoice {
+            waveform,
+            // 1 拍を 2 つに分ける
+            step: 30.0 / tempo,
+            volume,
+            notes,
+        })
+    }
+
+    fn render(&self) -> Vec<f32> {
+        let step_length = (self.step * SAMPLE_RATE as f32) as usize;
+        let mut samples = Vec::with_capacity(step_length * self.notes.len());
+        let mut oscillator = Oscillator::new();
+        for note in self.notes.iter() {
+            let Some(frequency) = note else {
+                samples.resize(samples.len() + step_length, 0.0);
+                continue;
+            };
+            for index in 0..step_length {
+                let progress = index as f32 / step_length as f32;
+                let wave = oscillator.sample(self.waveform, *frequency);
+                samples.push(wave * envelope(index, progress) * self.volume);
+            }
+        }
+        samples
+    }
+}
+
+// 組み込みの内容の後ろに assets のファイルを足す。後に書いた値が優先される
+fn preset_text(default: &str, file_name: &str) -> String {
+    let mut text = default.to_string();
+    let path = FileAssetIo::get_base_path().join("assets").join(file_name);
+    if let Ok(file) = fs::read_to_string(path) {
+        text.push('\n');
+        text.push_str(&file);
+    }
+    text
+}
+
+// 曲名ごとのパート。キーは `曲名.パート名` で、同じ曲名のものをまとめる
+pub fn load_songs() -> HashMap<String, Vec<SongVoice>> {
+    let mut voices = HashMap::new();
+    for (name, value) in
+        storage::parse_entries(&preset_text(DEFAULT_SONG_PRESETS, SONG_PRESETS_FILE))
+    {
+        match SongVoice::parse(&value) {
+            Some(voice) => {
+                voices.insert(name, voice);
+            }
+            None => warn!("Invalid song preset {}: {}", name, value),
+        }
+    }
+    let mut songs: HashMap<String, Vec<SongVoice>> = HashMap::new();
+    for (name, voice) in voices {
+        let song = name.split_once('.').map_or(name.as_str(), |(song, _)| song);
+        songs.entry(song.to_string()).or_default().push(voice);
+    }
+    songs
+}
+
+// 名前ごとのプリセット。assets のファイルで組み込みの値を上書きできる
+pub fn load_presets() -> HashMap<String, SynthPreset> {
+    let mut presets = HashMap::new();
+    for (name, value) in storage::parse_entries(&preset_text(DEFAULT_PRESETS, PRESETS_FILE)) {
+        match SynthPreset::parse(&value) {
+            Some(preset) => {
+                presets.insert(name, preset);
+            }
+            None => warn!("Invalid sound preset {}: {}", name, value),
+        }
+    }
+    presets
+}
+
+// 起動時に合成しておいた波形。Bevy の音源として再生する
+#[derive(Debug, Clone, TypeUuid, TypePath)]
+#[uuid = "5b8f0c1e-3d2a-4e6f-9a7b-1c4d8e2f6a90"]
+pub struct SynthSound(Arc<[f32]>);
+
+impl SynthSound {
+    pub fn new(preset: &SynthPreset) -> Self {
+        Self(preset.render().into())
+    }
+
+    // パートを重ねて 1 曲にする。短いパートの後ろは無音になる
+    pub fn from_song(voices: &[SongVoice]) -> Self {
+        let rendered: Vec<Vec<f32>> = voices.iter().map(SongVoice::render).collect();
+        let length = rendered.iter().map(Vec::len).max().unwrap_or(0);
+        let mut samples = vec![0.0_f32; length];
+        for voice in rendered.iter() {
+            for (sample, value) in samples.iter_mut().zip(voice.iter()) {
+                *sample += value;
+            }
+        }
+        for sample in samples.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+        Self(samples.into())
+    }
+}
+
+pub struct SynthDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+impl Decodable for SynthSound {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            samples: self.0.clone(),
+            position: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.expect("note should parse");
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn note_frequency_from_name() {
+        assert_close(note_frequency("A4"), 440.0);
+        assert_close(note_frequency("A5"), 880.0);
+        assert_close(note_frequency("C4"), 261.63);
+        assert_close(note_frequency("F#3"), 185.0);
+        assert_close(note_frequency("A#1"), 58.27);
+    }
+
+    #[test]
+    fn note_frequency_rejects_bad_names() {
+        for name in ["", "H4", "a4", "A", "A#", "Ab4", "C4x", "-"] {
+            assert_eq!(note_frequency(name), None, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn synth_preset_parse() {
+        let preset = SynthPreset::parse("square 880 220 0.2 1.5").unwrap();
+        assert_eq!(preset.waveform, Waveform::Square);
+        assert_eq!(preset.duration, 0.2);
+        // 音量は 0.0 ~ 1.0 に収める
+        assert_eq!(preset.volume, 1.0);
+    }
+
+    #[test]
+    fn synth_preset_parse_rejects_bad_values() {
+        for value in [
+            "",
+            "organ 880 220 0.2 0.5",
+            "square 880 220 0.2",
+            "square 880 abc 0.2 0.5",
+            "square 0 220 0.2 0.5",
+            "square 880 -1 0.2 0.5",
+            "square 880 220 0 0.5",
+        ] {
+            assert_eq!(SynthPreset::parse(value), None, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn song_voice_parse() {
+        let voice = SongVoice::parse("triangle 120 0.3 C4 - A4").unwrap();
+        assert_eq!(voice.waveform, Waveform::Triangle);
+        assert_eq!(voice.step, 0.25);
+        assert_eq!(voice.notes.len(), 3);
+        assert_eq!(voice.notes[1], None);
+        assert_close(voice.notes[2], 440.0);
+    }
+
+    #[test]
+    fn song_voice_parse_rejects_bad_values() {
+        for value in [
+            "",
+            "organ 120 0.3 C4",
+            "triangle fast 0.3 C4",
+            "triangle 0 0.3 C4",
+            "triangle 120 0.3",
+            "triangle 120 0.3 C4 X9",
+        ] {
+            assert_eq!(SongVoice::parse(value), None, "{:?}", value);
+        }
+    }
+}